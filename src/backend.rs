@@ -0,0 +1,216 @@
+//! Arithmetic backends for the encrypted part of signing.
+//!
+//! `FheBackend` captures the operations `Schnorr` and `EncryptedKeyTable` need on an encrypted
+//! integer: encryption, addition, multiplication and reduction modulo a clear modulus. It is
+//! implemented on the limb vector `BigUintFHE` and on tfhe's native radix integer `FheUint512`,
+//! whose operations are parallelised inside tfhe, so the two approaches can be compared on the
+//! same signing code. Native arithmetic wraps around silently, and `e * d` for 256-bit operands
+//! needs 512 bits, so there is deliberately no implementation on `FheUint256`.
+
+use tfhe::prelude::*;
+use tfhe::integer::bigint::StaticUnsignedBigInt;
+use tfhe::integer::U512;
+use tfhe::{ClientKey, FheUint512};
+use num_bigint::BigUint;
+use crate::biguint::BigUintFHE;
+use crate::carry_save::CarrySaveAccumulator;
+
+/// An encrypted unsigned integer type that signing can run on
+pub trait FheBackend: Clone {
+    /// Key used to encrypt and decrypt values
+    type ClientKey;
+
+    /// Encrypts a value with the client key
+    fn encrypt(value: &BigUint, client_key: &Self::ClientKey) -> Result<Self, tfhe::Error>;
+
+    /// Decrypts the value with the client key
+    fn decrypt(&self, client_key: &Self::ClientKey) -> BigUint;
+
+    /// Creates a trivially encrypted value from a public constant
+    fn trivial(value: &BigUint) -> Self;
+
+    /// Computes `self + other`
+    fn add(&self, other: &Self) -> Self;
+
+    /// Computes `self + other` for a plaintext `other`
+    fn add_clear(&self, other: &BigUint) -> Self;
+
+    /// Computes `self * other`
+    fn mul(&self, other: &Self) -> Self;
+
+    /// Computes `self * other` for a plaintext `other`
+    fn mul_clear(&self, other: &BigUint) -> Self;
+
+    /// Computes `self * mul + add` for plaintext `mul` and `add`
+    fn mul_add_clear(&self, mul: &BigUint, add: &BigUint) -> Self {
+        self.mul_clear(mul).add_clear(add)
+    }
+
+    /// Computes the sum of `values` and a plaintext `clear`
+    fn sum_add_clear(values: &[&Self], clear: &BigUint) -> Self {
+        values.iter().fold(Self::trivial(clear), |acc, value| acc.add(value))
+    }
+
+    /// Reduces the value modulo a plaintext modulus
+    fn reduce_mod(&self, modulus: &BigUint) -> Self;
+}
+
+impl FheBackend for BigUintFHE {
+    type ClientKey = ClientKey;
+
+    fn encrypt(value: &BigUint, client_key: &ClientKey) -> Result<Self, tfhe::Error> {
+        BigUintFHE::new(value.clone(), client_key)
+    }
+
+    fn decrypt(&self, client_key: &ClientKey) -> BigUint {
+        self.to_biguint(client_key)
+    }
+
+    fn trivial(value: &BigUint) -> Self {
+        BigUintFHE::trivial(value)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self.clone() + other.clone()
+    }
+
+    fn add_clear(&self, other: &BigUint) -> Self {
+        BigUintFHE::add_clear(self, other)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self.clone() * other.clone()
+    }
+
+    fn mul_clear(&self, other: &BigUint) -> Self {
+        BigUintFHE::mul_clear(self, other)
+    }
+
+    fn mul_add_clear(&self, mul: &BigUint, add: &BigUint) -> Self {
+        self.mul_add(mul, add)
+    }
+
+    fn sum_add_clear(values: &[&Self], clear: &BigUint) -> Self {
+        // All terms are collected in wide columns, so the sum costs one carry resolution
+        let bound = values.iter().fold(clear.clone(), |acc, value| acc + BigUintFHE::bound(value));
+        let mut acc = CarrySaveAccumulator::new(BigUintFHE::digit_count(&bound));
+        acc.add_clear(clear);
+        for value in values {
+            acc.add(value);
+        }
+        acc.normalize().with_bound(&bound)
+    }
+
+    fn reduce_mod(&self, modulus: &BigUint) -> Self {
+        BigUintFHE::reduce_mod(self, modulus)
+    }
+}
+
+/// Implements `FheBackend` on a native tfhe integer. Arithmetic wraps around at the type's
+/// width, so the type must be twice as wide as the signing operands.
+macro_rules! impl_native_backend {
+    ($fhe:ty, $clear:ident, $words:expr) => {
+        impl FheBackend for $fhe {
+            type ClientKey = ClientKey;
+
+            fn encrypt(value: &BigUint, client_key: &ClientKey) -> Result<Self, tfhe::Error> {
+                <$fhe>::try_encrypt(native_words::<$words>(value)?, client_key)
+            }
+
+            fn decrypt(&self, client_key: &ClientKey) -> BigUint {
+                let value: $clear = FheDecrypt::decrypt(self, client_key);
+                from_native(&value)
+            }
+
+            fn trivial(value: &BigUint) -> Self {
+                let words = native_words::<$words>(value).expect("Constant does not fit the native integer");
+                <$fhe>::encrypt_trivial(words)
+            }
+
+            fn add(&self, other: &Self) -> Self {
+                self + other
+            }
+
+            fn add_clear(&self, other: &BigUint) -> Self {
+                self + clear_operand::<$words>(other)
+            }
+
+            fn mul(&self, other: &Self) -> Self {
+                self * other
+            }
+
+            fn mul_clear(&self, other: &BigUint) -> Self {
+                self * clear_operand::<$words>(other)
+            }
+
+            fn reduce_mod(&self, modulus: &BigUint) -> Self {
+                assert!(*modulus != BigUint::from(0u32), "Cannot reduce modulo zero");
+                // tfhe divides by a clear scalar with multiplications and shifts only
+                self % clear_operand::<$words>(modulus)
+            }
+        }
+    };
+}
+
+impl_native_backend!(FheUint512, U512, 8);
+
+/// Splits a clear value into the `N` little-endian u64 words of a native clear integer
+fn native_words<const N: usize>(value: &BigUint) -> Result<StaticUnsignedBigInt<N>, tfhe::Error> {
+    let digits = value.to_u64_digits();
+    if digits.len() > N {
+        return Err(format!("Value needs {} words, more than the {} available", digits.len(), N).into());
+    }
+    let mut words = [0u64; N];
+    words[..digits.len()].copy_from_slice(&digits);
+    Ok(StaticUnsignedBigInt::from(words))
+}
+
+/// Like `native_words` for a public operand, which has to fit the native width
+fn clear_operand<const N: usize>(value: &BigUint) -> StaticUnsignedBigInt<N> {
+    native_words::<N>(value).expect("Clear operand does not fit the native integer")
+}
+
+/// Rebuilds a BigUint from a native clear integer
+fn from_native<const N: usize>(value: &StaticUnsignedBigInt<N>) -> BigUint {
+    let mut bytes = vec![0u8; N * 8];
+    value.copy_to_le_byte_slice(&mut bytes);
+    BigUint::from_bytes_le(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tfhe::ConfigBuilder;
+    use crate::scalar::get_curve_order;
+
+    /// Computes `(k + e * d) mod n` the way signing does
+    fn sign_step<B: FheBackend>(d: &B, e: &BigUint, k: &BigUint, n: &BigUint) -> B {
+        d.mul_add_clear(e, k).reduce_mod(n)
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let n = get_curve_order();
+        let d = &n - BigUint::from(3u32);
+        let e = &n - BigUint::from(12345u32);
+        let k = &n - BigUint::from(1u32);
+        let expected = (&k + &e * &d) % &n;
+
+        let limbs = <BigUintFHE as FheBackend>::encrypt(&d, &client_key).unwrap();
+        assert_eq!(sign_step(&limbs, &e, &k, &n).decrypt(&client_key), expected);
+
+        let native = <FheUint512 as FheBackend>::encrypt(&d, &client_key).unwrap();
+        // FheDecrypt is also in scope for native integers, so the trait is named explicitly
+        assert_eq!(FheBackend::decrypt(&sign_step(&native, &e, &k, &n), &client_key), expected);
+
+        // Values wider than the native integer are rejected
+        assert!(<FheUint512 as FheBackend>::encrypt(&(BigUint::from(1u32) << 512), &client_key).is_err());
+        let small = <FheUint512 as FheBackend>::encrypt(&BigUint::from(7u32), &client_key).unwrap();
+        let sum = FheBackend::add(&small, &<FheUint512 as FheBackend>::trivial(&BigUint::from(5u32)));
+        assert_eq!(FheBackend::decrypt(&sum, &client_key), BigUint::from(12u32));
+    }
+}
@@ -1,22 +1,46 @@
-use std::ops::{Add, Mul};
-use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use std::sync::atomic::{AtomicU64, Ordering};
 use tfhe::prelude::*;
-use tfhe::{FheUint32, FheUint64, ClientKey};
+use tfhe::{FheBool, FheUint32, FheUint64, ClientKey, CompactCiphertextList, CompactPublicKey, PublicKey, ServerKey};
+use rayon::prelude::*;
 use num_bigint::BigUint;
-use std::time::Instant;
-
+use crate::carry_save::{AccumulatorOperand, CarrySaveAccumulator};
+
+/// Operands with at least this many digits are multiplied with Karatsuba instead of schoolbook.
+///
+/// One Karatsuba step replaces four half-size products with three, at the price of a few
+/// encrypted additions and subtractions, which only pays off once the products dominate.
+pub const KARATSUBA_THRESHOLD: usize = 8;
+
+/// Number of exponent bits consumed per multiplication in `pow_mod_clear`.
+///
+/// A window of w bits costs up to 2^w - 2 multiplications to build the table of powers and saves
+/// one multiplication per window instead of one per set bit.
+pub const POW_WINDOW_BITS: usize = 4;
+
+/// Source of fresh `BigUintFHE` ids
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// An arbitrary-size unsigned integer encrypted digit by digit.
+///
+/// Values carry only ciphertexts: encryption and decryption take the `ClientKey` explicitly,
+/// while all arithmetic runs with just the `ServerKey` set on the current thread.
 #[derive(Clone)]
 pub struct BigUintFHE {
     // Represent the number as a vector of encrypted u32 digits, least significant digit first
     digits: Vec<FheUint32>,
-    client_key: ClientKey,
+    // Public upper bound of the value. There are exactly as many digits as the bound needs, so
+    // no top digit is provably zero.
+    bound: BigUint,
+    // Shared by clones only, so equal ids mean the same ciphertexts and `Mul` can square
+    id: u64,
 }
 
 impl BigUintFHE {
-    /// Creates a new BigUintFHE from a BigUint value
+    /// Encrypts a BigUint value with the client key
     pub fn new(value: BigUint, client_key: &ClientKey) -> Result<Self, tfhe::Error> {
         if value == BigUint::from(0u32) {
-            Ok(Self { digits: vec![], client_key: client_key.clone() })
+            Ok(Self::zero())
         } else {
             // Convert BigUint to a vector of u32 digits
             let digits: Vec<u32> = value.to_u32_digits();
@@ -26,35 +50,119 @@ impl BigUintFHE {
                 .map(|d| FheUint32::try_encrypt(d, client_key))
                 .collect::<Result<Vec<_>, _>>()?;
 
-            Ok(Self { digits: encrypted_digits, client_key: client_key.clone() })
+            Ok(Self::from_encrypted_digits(encrypted_digits))
         }
     }
 
-    /// Creates a new BigUintFHE from a u32 value
+    /// Encrypts a u32 value with the client key
     pub fn from_u32(value: u32, client_key: &ClientKey) -> Result<Self, tfhe::Error> {
         Self::new(BigUint::from(value), client_key)
     }
 
-    /// Normalize the digits vector by removing trailing zeros
+    /// Encrypts a BigUint value with a public key, for parties that do not hold the client key
+    pub fn new_with_public_key(value: BigUint, public_key: &PublicKey) -> Result<Self, tfhe::Error> {
+        let digits = value.to_u32_digits().into_iter()
+            .map(|d| FheUint32::try_encrypt(d, public_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_encrypted_digits(digits))
+    }
+
+    /// Encrypts a BigUint value with a compact public key.
+    ///
+    /// The digits are packed into one `CompactCiphertextList`, which is much smaller than the
+    /// expanded ciphertexts and is what a device should send. Use `from_compact_list` to expand it.
+    pub fn encrypt_compact(value: &BigUint, public_key: &CompactPublicKey) -> CompactCiphertextList {
+        CompactCiphertextList::builder(public_key)
+            .extend(value.to_u32_digits().into_iter())
+            .build()
+    }
+
+    /// Expands a list produced by `encrypt_compact` into a BigUintFHE
+    pub fn from_compact_list(list: &CompactCiphertextList) -> Result<Self, tfhe::Error> {
+        let expander = list.expand()?;
+        let digits = (0..expander.len())
+            .map(|i| expander.get::<FheUint32>(i)?
+                .ok_or_else(|| tfhe::Error::from(format!("Missing digit {} in compact list", i))))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_encrypted_digits(digits))
+    }
+
+    /// Encrypts a BigUint value with a compact public key and expands it right away
+    pub fn new_with_compact_public_key(value: BigUint, public_key: &CompactPublicKey) -> Result<Self, tfhe::Error> {
+        Self::from_compact_list(&Self::encrypt_compact(&value, public_key))
+    }
+
+    /// Creates a trivially encrypted BigUintFHE from a public value.
+    ///
+    /// Trivial ciphertexts need no key and hide nothing, they only let public constants take part
+    /// in homomorphic arithmetic on the server.
+    pub fn trivial(value: &BigUint) -> Self {
+        let digits = value.to_u32_digits().into_iter()
+            .map(FheUint32::encrypt_trivial)
+            .collect();
+        // The value itself is public, so it is the tightest bound
+        Self::from_parts(digits, value.clone())
+    }
+
+    /// Builds a value from its digits and a public upper bound on it
+    fn from_parts(digits: Vec<FheUint32>, bound: BigUint) -> Self {
+        let mut value = Self { digits, bound, id: BigUintFHE::next_id() };
+        value.normalize();
+        value
+    }
+
+    /// Caps the bound at what the digits can hold and drops the top digits that the bound
+    /// proves to be zero. Ciphertexts can't be inspected, but the bound is public.
     fn normalize(&mut self) {
-        // Note: We can't easily check for zeros in encrypted form
-        // This would require decryption which we don't want to do during computation
-        // So we'll keep all digits for now
+        let capacity = BigUintFHE::max_value(self.digits.len());
+        if self.bound > capacity {
+            self.bound = capacity;
+        }
+        self.digits.truncate(BigUintFHE::digit_count(&self.bound));
+    }
+
+    /// Creates a BigUint from a vector of encrypted u32 digits.
+    ///
+    /// Nothing is known about the digits, so the bound is the largest value they can hold. Use
+    /// `with_bound` to declare a tighter one.
+    pub fn from_encrypted_digits(digits: Vec<FheUint32>) -> Self {
+        let bound = BigUintFHE::max_value(digits.len());
+        Self { digits, bound, id: BigUintFHE::next_id() }
     }
 
-    /// Creates a BigUint from a vector of encrypted u32 digits
-    pub fn from_encrypted_digits(digits: Vec<FheUint32>, client_key: &ClientKey) -> Self {
-        Self { digits, client_key: client_key.clone() }
+    /// Returns the encrypted u32 digits, least significant digit first
+    pub fn digits(&self) -> &[FheUint32] {
+        &self.digits
+    }
+
+    /// Returns the public upper bound of the value.
+    ///
+    /// Every operation derives the bound of its result from the bounds of its operands and sizes
+    /// the result from it, so no digit is spent on a value that is provably zero.
+    pub fn bound(&self) -> &BigUint {
+        &self.bound
+    }
+
+    /// Declares a public upper bound known from the protocol, such as `d < n` for a private key.
+    ///
+    /// The bound is not checked: the caller guarantees that the encrypted value does not exceed
+    /// it. Top digits above the bound are dropped. A bound looser than the current one is ignored.
+    pub fn with_bound(mut self, bound: &BigUint) -> Self {
+        if *bound < self.bound {
+            self.bound = bound.clone();
+            self.normalize();
+        }
+        self
     }
 
     /// Returns zero
-    pub fn zero(client_key: &ClientKey) -> Result<Self, tfhe::Error> {
-        Ok(Self { digits: Vec::<FheUint32>::new(), client_key: client_key.clone() })
+    pub fn zero() -> Self {
+        Self { digits: Vec::<FheUint32>::new(), bound: BigUint::from(0u32), id: BigUintFHE::next_id() }
     }
 
-    /// Returns one
-    pub fn one(client_key: &ClientKey) -> Result<Self, tfhe::Error> {
-        Self::from_u32(1, client_key)
+    /// Returns a trivially encrypted one
+    pub fn one() -> Self {
+        Self::trivial(&BigUint::from(1u32))
     }
 
     /// Decrypts the BigUintFHE to a BigUint
@@ -104,6 +212,831 @@ impl BigUintFHE {
         }
     }
 
+    /// Adds a plaintext BigUint to the encrypted value.
+    ///
+    /// The clear operand is never encrypted: its u32 digits are added to the encrypted digits as
+    /// scalars. Like `Add`, the result only gets an extra carry digit if the bounds allow one.
+    pub fn add_clear(&self, other: &BigUint) -> Self {
+        let clear_digits = other.to_u32_digits();
+        let max_len = std::cmp::max(self.digits.len(), clear_digits.len());
+        let bound = &self.bound + other;
+        let out_len = BigUintFHE::digit_count(&bound);
+        let mut result = Vec::with_capacity(out_len);
+        let mut carry: Option<FheUint64> = None;
+
+        for i in 0..max_len {
+            let b = clear_digits.get(i).copied().unwrap_or(0);
+
+            let sum = match (self.digits.get(i), carry.take()) {
+                (Some(a), c) => {
+                    if b == 0 && c.is_none() {
+                        result.push(a.clone());
+                        continue;
+                    }
+                    let mut temp_sum = FheUint64::cast_from(a.clone());
+                    if b != 0 {
+                        temp_sum += b as u64;
+                    }
+                    if let Some(c) = c {
+                        temp_sum += c;
+                    }
+                    temp_sum
+                },
+                (None, Some(c)) => c + b as u64,
+                (None, None) => {
+                    // Both the digit and the carry are public here
+                    result.push(FheUint32::encrypt_trivial(b));
+                    continue;
+                },
+            };
+
+            result.push(BigUintFHE::extract_lower_bits(&sum));
+            // The carry out of the top digit is provably zero when the bound fits in max_len digits
+            if i + 1 < out_len {
+                carry = Some(sum >> 32u64);
+            }
+        }
+
+        if let Some(c) = carry {
+            result.push(FheUint32::cast_from(c));
+        }
+
+        BigUintFHE::from_parts(result, bound)
+    }
+
+    /// Multiplies the encrypted value by a plaintext BigUint.
+    ///
+    /// Each partial product is an encrypted-by-clear multiplication, which is much cheaper than
+    /// encrypting the operand and running the encrypted-by-encrypted `Mul`. Zero digits of the
+    /// clear operand are skipped entirely.
+    pub fn mul_clear(&self, other: &BigUint) -> Self {
+        let clear_digits = other.to_u32_digits();
+        let bound = &self.bound * other;
+        if bound == BigUint::from(0u32) {
+            return Self::zero();
+        }
+
+        let out_len = BigUintFHE::digit_count(&bound);
+        let digits = BigUintFHE::mul_clear_digits(&self.digits, &clear_digits, out_len);
+
+        BigUintFHE::from_parts(digits, bound)
+    }
+
+    /// Computes `self * mul + add` with a single carry resolution.
+    ///
+    /// The addend is folded into the carry-save accumulator of the product, so carries are
+    /// extracted once instead of once in `Mul` and again in `Add`, and no operand is cloned.
+    /// Both `mul` and `add` can be clear (`BigUint`) or encrypted (`BigUintFHE`).
+    pub fn mul_add<X, Y>(&self, mul: &X, add: &Y) -> Self
+    where
+        X: AccumulatorOperand,
+        Y: AccumulatorOperand,
+    {
+        // The result needs exactly as many digits as its bound, e.g. k + e * d < n^2 fits in
+        // 2 * 256 bits, where sizing from the digit counts would add a carry digit
+        let bound = &self.bound * mul.bound() + add.bound();
+        let len = BigUintFHE::digit_count(&bound);
+
+        let mut acc = CarrySaveAccumulator::new(len);
+        mul.add_product_to(self, &mut acc);
+        add.add_to(&mut acc);
+        acc.normalize().with_bound(&bound)
+    }
+
+    /// Squares the encrypted value.
+    ///
+    /// Each cross product `a_i * a_j` with `i < j` is computed once and added twice, so an
+    /// `n`-digit square costs `n * (n + 1) / 2` encrypted digit products instead of `n^2`. `Mul`
+    /// calls this when both operands are clones of the same value.
+    pub fn square(&self) -> Self {
+        let bound = &self.bound * &self.bound;
+        if bound == BigUint::from(0u32) {
+            return Self::zero();
+        }
+
+        let mut acc = CarrySaveAccumulator::new(BigUintFHE::digit_count(&bound));
+        acc.add_square(self);
+        acc.normalize().with_bound(&bound)
+    }
+
+    /// Multiplies two encrypted values, computing every digit product in parallel.
+    ///
+    /// tfhe keeps the server key in a thread-local, so it is set on each rayon worker before use.
+    /// The 64-bit products are split into low and high halves and summed per column, then a
+    /// single ripple pass resolves the column carries. Returns the same digits as `Mul`.
+    pub fn par_mul(&self, other: &Self, server_key: &ServerKey) -> Self {
+        let bound = &self.bound * &other.bound;
+        if bound == BigUint::from(0u32) {
+            return Self::zero();
+        }
+
+        let out_len = BigUintFHE::digit_count(&bound);
+        let pairs: Vec<(usize, usize)> = (0..self.digits.len())
+            .flat_map(|i| (0..other.digits.len()).map(move |j| (i, j)))
+            .filter(|&(i, j)| i + j < out_len)
+            .collect();
+
+        let halves: Vec<(usize, FheUint32, FheUint32)> = pairs.par_iter()
+            .map_init(
+                || tfhe::set_server_key(server_key.clone()),
+                |_, &(i, j)| {
+                    let a64 = FheUint64::cast_from(self.digits[i].clone());
+                    let b64 = FheUint64::cast_from(other.digits[j].clone());
+                    let product = a64 * b64;
+                    (i + j, BigUintFHE::extract_lower_bits(&product), BigUintFHE::extract_upper_bits(&product))
+                },
+            )
+            .collect();
+
+        // Column c collects the low halves of products with i + j = c and the high halves of
+        // products with i + j = c - 1. At most 2 * min(n, m) terms below 2^32 fit in a u64.
+        let mut columns: Vec<Vec<FheUint32>> = vec![Vec::new(); out_len];
+        for (idx, lower, upper) in halves {
+            columns[idx].push(lower);
+            // High halves above the bound are provably zero
+            if idx + 1 < out_len {
+                columns[idx + 1].push(upper);
+            }
+        }
+
+        let sums: Vec<Option<FheUint64>> = columns.into_par_iter()
+            .map_init(
+                || tfhe::set_server_key(server_key.clone()),
+                |_, column| column.into_iter().map(FheUint64::cast_from).reduce(|acc, t| acc + t),
+            )
+            .collect();
+
+        let mut digits = Vec::with_capacity(out_len);
+        let mut carry: Option<FheUint64> = None;
+        for sum in sums {
+            let total = match (sum, carry.take()) {
+                (Some(s), Some(c)) => s + c,
+                (Some(s), None) => s,
+                (None, Some(c)) => c,
+                (None, None) => {
+                    digits.push(FheUint32::encrypt_trivial(0u32));
+                    continue;
+                },
+            };
+            digits.push(BigUintFHE::extract_lower_bits(&total));
+            carry = Some(total >> 32u64);
+        }
+
+        BigUintFHE::from_parts(digits, bound)
+    }
+
+    /// Adds two encrypted values with a parallel-prefix (Kogge-Stone) carry-lookahead adder.
+    ///
+    /// Every digit sum, with its generate and propagate flags, is computed concurrently, then
+    /// the carries are resolved in log2(digits) rounds of flag combination instead of a ripple
+    /// through every digit. Like `par_mul` the server key is set on each rayon worker. Returns
+    /// the same digits as `Add`.
+    pub fn par_add(&self, other: &Self, server_key: &ServerKey) -> Self {
+        let len = std::cmp::max(self.digits.len(), other.digits.len());
+        if len == 0 {
+            return Self::zero();
+        }
+        let bound = &self.bound + &other.bound;
+
+        // Per digit: the lower 32 bits of a_i + b_i, whether it carries out on its own (generate)
+        // and whether an incoming carry would pass through it (propagate)
+        let partials: Vec<(FheUint32, FheBool, FheBool)> = (0..len).into_par_iter()
+            .map_init(
+                || tfhe::set_server_key(server_key.clone()),
+                |_, i| match (self.digits.get(i), other.digits.get(i)) {
+                    (Some(a), Some(b)) => {
+                        let sum = FheUint64::cast_from(a.clone()) + FheUint64::cast_from(b.clone());
+                        let lower = BigUintFHE::extract_lower_bits(&sum);
+                        let propagate = lower.eq(u32::MAX);
+                        (lower, sum.gt(0xFFFFFFFFu64), propagate)
+                    },
+                    (Some(d), None) | (None, Some(d)) => (d.clone(), FheBool::encrypt_trivial(false), d.eq(u32::MAX)),
+                    (None, None) => unreachable!(),
+                },
+            )
+            .collect();
+
+        let (lowers, flags): (Vec<FheUint32>, Vec<(FheBool, FheBool)>) = partials.into_iter()
+            .map(|(lower, generate, propagate)| (lower, (generate, propagate)))
+            .unzip();
+        let carries = BigUintFHE::prefix_carries(flags, server_key);
+
+        // Digit i receives the carry out of digits 0..i, the last carry becomes the top digit
+        let mut digits: Vec<FheUint32> = lowers.into_par_iter()
+            .enumerate()
+            .map_init(
+                || tfhe::set_server_key(server_key.clone()),
+                |_, (i, lower)| match i {
+                    0 => lower,
+                    _ => lower + FheUint32::cast_from(carries[i - 1].clone()),
+                },
+            )
+            .collect();
+        if BigUintFHE::digit_count(&bound) > len {
+            digits.push(FheUint32::cast_from(carries[len - 1].clone()));
+        }
+
+        BigUintFHE::from_parts(digits, bound)
+    }
+
+    /// Adds up any number of encrypted values with a Wallace tree of carry-save adders.
+    ///
+    /// Each level splits the operands into groups of three and compresses every group into a
+    /// digit-wise sum and a carry vector, without propagating any carry. Groups are compressed in
+    /// parallel on rayon workers, which get the server key like in `par_mul`. Once two operands
+    /// remain, `par_add` resolves the carries in a single pass. The result is sized from the sum
+    /// of the bounds.
+    pub fn sum<I: IntoIterator<Item = Self>>(values: I, server_key: &ServerKey) -> Self {
+        let mut level: Vec<Self> = values.into_iter()
+            .filter(|value| !value.digits.is_empty())
+            .collect();
+        let bound = level.iter().fold(BigUint::from(0u32), |acc, value| acc + &value.bound);
+
+        while level.len() > 2 {
+            let mut groups = Vec::with_capacity(level.len().div_ceil(3));
+            let mut operands = level.into_iter();
+            loop {
+                let group: Vec<Self> = operands.by_ref().take(3).collect();
+                if group.is_empty() {
+                    break;
+                }
+                groups.push(group);
+            }
+
+            // One or two leftover operands move to the next level unchanged
+            let compressed: Vec<Vec<Self>> = groups.into_par_iter()
+                .map_init(
+                    || tfhe::set_server_key(server_key.clone()),
+                    |_, group| {
+                        if group.len() < 3 {
+                            return group;
+                        }
+                        let (sum, carries) = BigUintFHE::compress_3_2(&group[0], &group[1], &group[2]);
+                        vec![sum, carries]
+                    },
+                )
+                .collect();
+            level = compressed.into_iter().flatten().collect();
+        }
+
+        match level.len() {
+            0 => Self::zero(),
+            1 => level.pop().unwrap(),
+            _ => level[0].par_add(&level[1], server_key).with_bound(&bound),
+        }
+    }
+
+    /// Multiplies two encrypted values with Karatsuba, recursing while both operands have at
+    /// least `threshold` digits (and never fewer than four) and falling back to schoolbook below that.
+    ///
+    /// `Mul` calls this with `KARATSUBA_THRESHOLD`. The result has at most `len(self) + len(other)`
+    /// digits, fewer when the bounds prove the top ones zero.
+    pub fn karatsuba_mul(&self, other: &Self, threshold: usize) -> Self {
+        if self.digits.is_empty() || other.digits.is_empty() {
+            return Self::zero();
+        }
+
+        let digits = BigUintFHE::karatsuba_digits(&self.digits, &other.digits, threshold);
+        BigUintFHE::from_parts(digits, &self.bound * &other.bound)
+    }
+
+    /// Subtracts `other` from `self`, returning the difference and an encrypted borrow flag.
+    ///
+    /// The difference has as many digits as the longer operand. When `other > self` the result
+    /// wraps around modulo 2^(32 * digits) and the borrow flag is true.
+    pub fn overflowing_sub(&self, other: &Self) -> (Self, FheBool) {
+        let len = std::cmp::max(self.digits.len(), other.digits.len());
+        let (digits, borrow) = BigUintFHE::sub_digits(&self.digits, &other.digits, len);
+
+        // The difference may wrap around, so only the digit count bounds it
+        (Self::from_encrypted_digits(digits), borrow)
+    }
+
+    /// Returns an encrypted `self < other`
+    pub fn lt(&self, other: &Self) -> FheBool {
+        BigUintFHE::fold_lt(BigUintFHE::cmp_flags(&self.digits, &other.digits), false)
+    }
+
+    /// Returns an encrypted `self <= other`
+    pub fn le(&self, other: &Self) -> FheBool {
+        BigUintFHE::fold_lt(BigUintFHE::cmp_flags(&self.digits, &other.digits), true)
+    }
+
+    /// Returns an encrypted `self > other`
+    pub fn gt(&self, other: &Self) -> FheBool {
+        !self.le(other)
+    }
+
+    /// Returns an encrypted `self >= other`
+    pub fn ge(&self, other: &Self) -> FheBool {
+        !self.lt(other)
+    }
+
+    /// Returns an encrypted `self == other`
+    pub fn eq(&self, other: &Self) -> FheBool {
+        BigUintFHE::fold_eq(BigUintFHE::cmp_flags(&self.digits, &other.digits))
+    }
+
+    /// Returns an encrypted `self < other` for a plaintext `other`
+    pub fn lt_clear(&self, other: &BigUint) -> FheBool {
+        BigUintFHE::fold_lt(BigUintFHE::cmp_clear_flags(&self.digits, &other.to_u32_digits()), false)
+    }
+
+    /// Returns an encrypted `self <= other` for a plaintext `other`
+    pub fn le_clear(&self, other: &BigUint) -> FheBool {
+        BigUintFHE::fold_lt(BigUintFHE::cmp_clear_flags(&self.digits, &other.to_u32_digits()), true)
+    }
+
+    /// Returns an encrypted `self > other` for a plaintext `other`
+    pub fn gt_clear(&self, other: &BigUint) -> FheBool {
+        !self.le_clear(other)
+    }
+
+    /// Returns an encrypted `self >= other` for a plaintext `other`
+    pub fn ge_clear(&self, other: &BigUint) -> FheBool {
+        !self.lt_clear(other)
+    }
+
+    /// Returns an encrypted `self == other` for a plaintext `other`
+    pub fn eq_clear(&self, other: &BigUint) -> FheBool {
+        BigUintFHE::fold_eq(BigUintFHE::cmp_clear_flags(&self.digits, &other.to_u32_digits()))
+    }
+
+    /// Returns an encrypted `self == 0`
+    pub fn is_zero(&self) -> FheBool {
+        self.eq_clear(&BigUint::from(0u32))
+    }
+
+    /// Obliviously returns `a` when `cond` is true and `b` otherwise.
+    ///
+    /// Every digit goes through `if_then_else`, so nothing about `cond` is revealed. The result
+    /// has as many digits as the longer input, and the larger of the two bounds.
+    pub fn select(cond: &FheBool, a: &Self, b: &Self) -> Self {
+        let digits = BigUintFHE::select_digits(cond, &a.digits, &b.digits);
+        BigUintFHE::from_parts(digits, std::cmp::max(&a.bound, &b.bound).clone())
+    }
+
+    /// Replaces `self` with `other` when `cond` is true and keeps it otherwise
+    pub fn conditional_assign(&mut self, cond: &FheBool, other: &Self) {
+        *self = BigUintFHE::select(cond, other, self);
+    }
+
+    /// Reduces the encrypted value modulo a plaintext modulus.
+    ///
+    /// The result is the canonical representative below `modulus`, encrypted with at most as many
+    /// digits as the modulus has. Uses Barrett reduction, so only encrypted-by-clear products,
+    /// subtractions and oblivious selects are needed. The bound skips work where it can: values
+    /// provably below the modulus are returned as is, and values below twice the modulus only
+    /// need one conditional subtraction.
+    pub fn reduce_mod(&self, modulus: &BigUint) -> Self {
+        assert!(*modulus != BigUint::from(0u32), "Cannot reduce modulo zero");
+
+        if self.bound < *modulus {
+            return self.clone();
+        }
+
+        let m_digits = modulus.to_u32_digits();
+        let k = m_digits.len();
+
+        let (mut r, subtractions) = if self.bound < modulus * 2u32 {
+            (self.digits.clone(), 1)
+        } else {
+            // At most two conditional subtractions bring the Barrett remainder below m
+            (self.barrett_estimate(&m_digits).1, 2)
+        };
+
+        for _ in 0..subtractions {
+            let (diff, borrow) = BigUintFHE::sub_clear_digits(&r, &m_digits);
+            r = BigUintFHE::select_digits(&borrow, &r, &diff);
+        }
+        r.truncate(k);
+
+        BigUintFHE::from_parts(r, modulus - 1u32)
+    }
+
+    /// Divides the encrypted value by a plaintext divisor, returning `(quotient, remainder)`.
+    ///
+    /// Uses the same Barrett estimate as `reduce_mod`, so no encrypted division is needed: the
+    /// estimated quotient is corrected by counting the conditional subtractions of the remainder.
+    /// Both results are sized from the bound: the quotient has at most `digits - divisor digits + 1`
+    /// digits and the remainder at most as many digits as the divisor.
+    pub fn div_rem_clear(&self, divisor: &BigUint) -> (Self, Self) {
+        assert!(*divisor != BigUint::from(0u32), "Cannot divide by zero");
+
+        if self.bound < *divisor {
+            return (Self::zero(), self.clone());
+        }
+
+        let m_digits = divisor.to_u32_digits();
+        let k = m_digits.len();
+        let q_bound = &self.bound / divisor;
+
+        let (q3, mut r) = self.barrett_estimate(&m_digits);
+
+        // Each subtraction that does not borrow means the estimate was one too low
+        let mut correction: Option<FheUint32> = None;
+        for _ in 0..2 {
+            let (diff, borrow) = BigUintFHE::sub_clear_digits(&r, &m_digits);
+            r = BigUintFHE::select_digits(&borrow, &r, &diff);
+            let step = FheUint32::cast_from(!borrow);
+            correction = Some(match correction {
+                Some(c) => c + step,
+                None => step,
+            });
+        }
+        r.truncate(k);
+
+        // floor(x / m) <= floor(bound / m), so the corrected quotient fits in the digits of q_bound
+        let q_len = BigUintFHE::digit_count(&q_bound);
+        let (q, _) = BigUintFHE::add_digits(&q3, &[correction.unwrap()], q_len);
+
+        (BigUintFHE::from_parts(q, q_bound), BigUintFHE::from_parts(r, divisor - 1u32))
+    }
+
+    /// Barrett estimate in base b = 2^32 for a value of n digits and a modulus m of k <= n digits.
+    ///
+    /// Returns the estimated quotient q3 and `x - q3 * m` on k + 1 digits, which is below 3m.
+    fn barrett_estimate(&self, m_digits: &[u32]) -> (Vec<FheUint32>, Vec<FheUint32>) {
+        let k = m_digits.len();
+        let n = self.digits.len();
+        let modulus = BigUint::new(m_digits.to_vec());
+
+        // Barrett reduction with mu = floor(b^n / m). Because x < b^n, the estimate
+        // q3 = floor(floor(x / b^(k-1)) * mu / b^(n-k+1)) is at most 2 below floor(x / m).
+        let mu = (BigUint::from(1u32) << (32 * n)) / modulus;
+        let mu_digits = mu.to_u32_digits();
+        let q1 = &self.digits[k - 1..];
+        let q2 = BigUintFHE::mul_clear_digits(q1, &mu_digits, q1.len() + mu_digits.len());
+        let q3 = q2[n - k + 1..].to_vec();
+
+        // x - q3 * m < 3m < b^(k+1), so both sides can be truncated to k + 1 digits
+        let r1 = &self.digits[..std::cmp::min(n, k + 1)];
+        let r2 = BigUintFHE::mul_clear_digits(&q3, m_digits, k + 1);
+        let (r, _) = BigUintFHE::sub_digits(r1, &r2, k + 1);
+
+        (q3, r)
+    }
+
+    /// Computes `(self + other) mod modulus`
+    pub fn add_mod(&self, other: &Self, modulus: &BigUint) -> Self {
+        (self.clone() + other.clone()).reduce_mod(modulus)
+    }
+
+    /// Computes `(self * other) mod modulus`
+    pub fn mul_mod(&self, other: &Self, modulus: &BigUint) -> Self {
+        (self.clone() * other.clone()).reduce_mod(modulus)
+    }
+
+    /// Computes `self^exp mod modulus` for a plaintext exponent.
+    ///
+    /// Fixed-window square-and-multiply over `POW_WINDOW_BITS`-bit windows of the exponent. The
+    /// exponent is public, so zero windows skip their multiplication without revealing anything
+    /// about `self`. The result has at most as many digits as the modulus.
+    pub fn pow_mod_clear(&self, exp: &BigUint, modulus: &BigUint) -> Self {
+        assert!(*modulus != BigUint::from(0u32), "Cannot reduce modulo zero");
+
+        // Windows of the exponent, most significant first
+        let window_count = (exp.bits() as usize).div_ceil(POW_WINDOW_BITS);
+        let windows: Vec<usize> = (0..window_count)
+            .rev()
+            .map(|i| {
+                (0..POW_WINDOW_BITS)
+                    .filter(|b| exp.bit((i * POW_WINDOW_BITS + b) as u64))
+                    .fold(0, |acc, b| acc | (1 << b))
+            })
+            .collect();
+
+        // table[j - 1] = self^j mod modulus, up to the largest window that occurs
+        let base = self.reduce_mod(modulus);
+        let table_len = windows.iter().copied().max().unwrap_or(0);
+        let mut table = Vec::with_capacity(table_len);
+        if table_len > 0 {
+            table.push(base.clone());
+        }
+        while table.len() < table_len {
+            let next = table.last().unwrap().mul_mod(&base, modulus);
+            table.push(next);
+        }
+
+        // `None` stands for one until the first non-zero window
+        let mut result: Option<Self> = None;
+        for window in windows {
+            if let Some(r) = result.as_mut() {
+                for _ in 0..POW_WINDOW_BITS {
+                    *r = r.mul_mod(r, modulus);
+                }
+            }
+            if window != 0 {
+                result = Some(match result {
+                    Some(r) => r.mul_mod(&table[window - 1], modulus),
+                    None => table[window - 1].clone(),
+                });
+            }
+        }
+
+        result.unwrap_or_else(|| Self::one().reduce_mod(modulus))
+    }
+
+    /// Computes the inverse of `self` modulo a plaintext prime as `self^(modulus - 2)`.
+    ///
+    /// By Fermat's little theorem this is the inverse whenever `self` is not a multiple of the
+    /// modulus. Zero maps to zero, since an encrypted value cannot be rejected without revealing it.
+    pub fn inverse_mod(&self, modulus: &BigUint) -> Self {
+        assert!(*modulus >= BigUint::from(2u32), "Modulus must be a prime");
+        self.pow_mod_clear(&(modulus - BigUint::from(2u32)), modulus)
+    }
+
+    /// Multiplies encrypted digits by clear digits, keeping only the lowest `out_len` digits
+    /// of the product. Zero clear digits are skipped entirely.
+    pub(crate) fn mul_clear_digits(a: &[FheUint32], b: &[u32], out_len: usize) -> Vec<FheUint32> {
+        let mut acc = CarrySaveAccumulator::new(out_len);
+        acc.add_product_clear_digits(a, b);
+        acc.normalize_digits()
+    }
+
+    /// Adds encrypted digits `a` and `b` over `len` digits. Returns the sum modulo 2^(32 * len)
+    /// and whether the addition carried out. Missing digits are treated as zero.
+    pub(crate) fn add_digits(a: &[FheUint32], b: &[FheUint32], len: usize) -> (Vec<FheUint32>, FheBool) {
+        let mut result = Vec::with_capacity(len);
+        let mut carry: Option<FheUint64> = None;
+
+        for i in 0..len {
+            let sum = match (a.get(i), b.get(i), carry.take()) {
+                (Some(a), Some(b), c) => {
+                    let mut temp_sum = FheUint64::cast_from(a.clone()) + FheUint64::cast_from(b.clone());
+                    if let Some(c) = c {
+                        temp_sum += c;
+                    }
+                    temp_sum
+                },
+                (Some(d), None, Some(c)) | (None, Some(d), Some(c)) => FheUint64::cast_from(d.clone()) + c,
+                (Some(d), None, None) | (None, Some(d), None) => {
+                    result.push(d.clone());
+                    continue;
+                },
+                (None, None, Some(c)) => {
+                    result.push(FheUint32::cast_from(c));
+                    continue;
+                },
+                (None, None, None) => {
+                    result.push(FheUint32::encrypt_trivial(0u32));
+                    continue;
+                },
+            };
+
+            result.push(BigUintFHE::extract_lower_bits(&sum));
+            carry = Some(sum >> 32u64);
+        }
+
+        let carry = match carry {
+            Some(c) => c.ne(0u64),
+            None => FheBool::encrypt_trivial(false),
+        };
+        (result, carry)
+    }
+
+    /// Multiplies encrypted digits, keeping only the lowest `out_len` digits of the product.
+    /// The digit products are collected in a carry-save accumulator and carried once at the end.
+    pub(crate) fn mul_digits(a: &[FheUint32], b: &[FheUint32], out_len: usize) -> Vec<FheUint32> {
+        let mut acc = CarrySaveAccumulator::new(out_len);
+        acc.add_product_digits(a, b);
+        acc.normalize_digits()
+    }
+
+    /// Turns per-digit `(generate, propagate)` flags into the carry out of every digit prefix.
+    ///
+    /// Kogge-Stone scan: after the round with distance `d`, entry `i` covers digits `i + 1 - 2d..=i`.
+    /// The flags of a higher span `hi` and the adjacent lower span `lo` combine as
+    /// `(g_hi | (p_hi & g_lo), p_hi & p_lo)`, and all entries of a round combine in parallel.
+    fn prefix_carries(mut flags: Vec<(FheBool, FheBool)>, server_key: &ServerKey) -> Vec<FheBool> {
+        let mut distance = 1;
+        while distance < flags.len() {
+            let previous = &flags;
+            flags = (0..previous.len()).into_par_iter()
+                .map_init(
+                    || tfhe::set_server_key(server_key.clone()),
+                    |_, i| {
+                        let (g_hi, p_hi) = &previous[i];
+                        if i < distance {
+                            return (g_hi.clone(), p_hi.clone());
+                        }
+                        let (g_lo, p_lo) = &previous[i - distance];
+                        (g_hi | &(p_hi & g_lo), p_hi & p_lo)
+                    },
+                )
+                .collect();
+            distance *= 2;
+        }
+
+        flags.into_iter().map(|(generate, _)| generate).collect()
+    }
+
+    /// Multiplies two non-empty encrypted digit slices with Karatsuba, returning all
+    /// `a.len() + b.len()` digits of the product
+    fn karatsuba_digits(a: &[FheUint32], b: &[FheUint32], threshold: usize) -> Vec<FheUint32> {
+        let (n, m) = (a.len(), b.len());
+        let out_len = n + m;
+        // Below four digits the sums a0 + a1 would be as long as the operands themselves
+        if std::cmp::min(n, m) < std::cmp::max(threshold, 4) {
+            return BigUintFHE::mul_digits(a, b, out_len);
+        }
+
+        let h = std::cmp::max(n, m).div_ceil(2);
+        if std::cmp::min(n, m) <= h {
+            // Unbalanced operands: split only the longer one, x * y = x0 * y + x1 * y * b^h
+            let (x, y) = if n >= m { (a, b) } else { (b, a) };
+            let mut result = BigUintFHE::karatsuba_digits(&x[..h], y, threshold);
+            let high = BigUintFHE::karatsuba_digits(&x[h..], y, threshold);
+            let (upper, _) = BigUintFHE::add_digits(&result[h..], &high, out_len - h);
+            result.truncate(h);
+            result.extend(upper);
+            return result;
+        }
+
+        // a * b = z2 * b^2h + z1 * b^h + z0, where z1 = (a0 + a1)(b0 + b1) - z0 - z2
+        let (a0, a1) = a.split_at(h);
+        let (b0, b1) = b.split_at(h);
+        let z0 = BigUintFHE::karatsuba_digits(a0, b0, threshold);
+        let z2 = BigUintFHE::karatsuba_digits(a1, b1, threshold);
+
+        let a_sum = BigUintFHE::from_encrypted_digits(a0.to_vec()) + BigUintFHE::from_encrypted_digits(a1.to_vec());
+        let b_sum = BigUintFHE::from_encrypted_digits(b0.to_vec()) + BigUintFHE::from_encrypted_digits(b1.to_vec());
+        let mid = BigUintFHE::karatsuba_digits(&a_sum.digits, &b_sum.digits, threshold);
+        let (z1, _) = BigUintFHE::sub_digits(&mid, &z0, mid.len());
+        let (z1, _) = BigUintFHE::sub_digits(&z1, &z2, z1.len());
+
+        // z0 and z2 fill exactly 2h and out_len - 2h digits. z1 * b^h < b^out_len, so any digits
+        // of z1 beyond out_len - h are encrypted zeros and can be dropped.
+        let mut result = z0;
+        result.extend(z2);
+        let (upper, _) = BigUintFHE::add_digits(&result[h..], &z1, out_len - h);
+        result.truncate(h);
+        result.extend(upper);
+        result
+    }
+
+    /// Subtracts encrypted digits `b` from `a` over `len` digits. Returns the difference modulo
+    /// 2^(32 * len) and whether the subtraction borrowed. Missing digits are treated as zero.
+    pub(crate) fn sub_digits(a: &[FheUint32], b: &[FheUint32], len: usize) -> (Vec<FheUint32>, FheBool) {
+        let mut result = Vec::with_capacity(len);
+        // Each step computes a - b + 2^32 - 1 + not_borrow, whose bit 32 is the next not_borrow
+        let mut not_borrow: Option<FheUint64> = None;
+
+        for i in 0..len {
+            let mut diff = match a.get(i) {
+                Some(a) => FheUint64::cast_from(a.clone()),
+                None => FheUint64::encrypt_trivial(0u64),
+            };
+            if let Some(b) = b.get(i) {
+                diff -= FheUint64::cast_from(b.clone());
+            }
+            let temp = match not_borrow.take() {
+                Some(nb) => diff + 0xFFFFFFFFu64 + nb,
+                None => diff + 0x100000000u64,
+            };
+
+            result.push(BigUintFHE::extract_lower_bits(&temp));
+            not_borrow = Some(temp >> 32u64);
+        }
+
+        let borrow = match not_borrow {
+            Some(nb) => nb.eq(0u64),
+            None => FheBool::encrypt_trivial(false),
+        };
+        (result, borrow)
+    }
+
+    /// Subtracts clear digits `b` from encrypted digits `a`, over as many digits as `a` has.
+    /// Returns the difference modulo 2^(32 * a.len()) and whether the subtraction borrowed.
+    pub(crate) fn sub_clear_digits(a: &[FheUint32], b: &[u32]) -> (Vec<FheUint32>, FheBool) {
+        let mut result = Vec::with_capacity(a.len());
+        let mut not_borrow: Option<FheUint64> = None;
+
+        for (i, a_digit) in a.iter().enumerate() {
+            let b_digit = b.get(i).copied().unwrap_or(0) as u64;
+            let a64 = FheUint64::cast_from(a_digit.clone());
+            let temp = match not_borrow.take() {
+                Some(nb) => a64 + (0xFFFFFFFFu64 - b_digit) + nb,
+                None => a64 + (0x100000000u64 - b_digit),
+            };
+
+            result.push(BigUintFHE::extract_lower_bits(&temp));
+            not_borrow = Some(temp >> 32u64);
+        }
+
+        let borrow = match not_borrow {
+            Some(nb) => nb.eq(0u64),
+            None => FheBool::encrypt_trivial(b.iter().any(|&d| d != 0)),
+        };
+        (result, borrow)
+    }
+
+    /// Per-digit `(a_i < b_i, a_i == b_i)` flags, least significant digit first.
+    /// Digits missing from the shorter operand are treated as zero.
+    fn cmp_flags(a: &[FheUint32], b: &[FheUint32]) -> Vec<(FheBool, FheBool)> {
+        let len = std::cmp::max(a.len(), b.len());
+        (0..len)
+            .map(|i| match (a.get(i), b.get(i)) {
+                (Some(a), Some(b)) => (a.lt(b), a.eq(b)),
+                (Some(a), None) => (FheBool::encrypt_trivial(false), a.eq(0u32)),
+                (None, Some(b)) => (b.ne(0u32), b.eq(0u32)),
+                (None, None) => unreachable!(),
+            })
+            .collect()
+    }
+
+    /// Per-digit `(a_i < b_i, a_i == b_i)` flags against clear digits, least significant digit first.
+    fn cmp_clear_flags(a: &[FheUint32], b: &[u32]) -> Vec<(FheBool, FheBool)> {
+        let len = std::cmp::max(a.len(), b.len());
+        (0..len)
+            .map(|i| {
+                let b_digit = b.get(i).copied().unwrap_or(0);
+                match a.get(i) {
+                    Some(a) => (a.lt(b_digit), a.eq(b_digit)),
+                    // Both digits are public here
+                    None => (FheBool::encrypt_trivial(b_digit != 0), FheBool::encrypt_trivial(b_digit == 0)),
+                }
+            })
+            .collect()
+    }
+
+    /// Folds per-digit flags into `a < b`, or `a <= b` when `or_equal` is set. A higher digit
+    /// decides the comparison unless it is equal, in which case the lower digits do.
+    fn fold_lt(flags: Vec<(FheBool, FheBool)>, or_equal: bool) -> FheBool {
+        let mut lt = FheBool::encrypt_trivial(or_equal);
+        for (digit_lt, digit_eq) in flags {
+            lt = digit_lt | (digit_eq & lt);
+        }
+        lt
+    }
+
+    /// Folds per-digit flags into `a == b`
+    fn fold_eq(flags: Vec<(FheBool, FheBool)>) -> FheBool {
+        flags.into_iter()
+            .map(|(_, digit_eq)| digit_eq)
+            .reduce(|acc, digit_eq| acc & digit_eq)
+            .unwrap_or_else(|| FheBool::encrypt_trivial(true))
+    }
+
+    /// Obliviously picks `a` when `cond` is true and `b` otherwise, digit by digit.
+    /// The shorter input is padded with zero digits.
+    pub(crate) fn select_digits(cond: &FheBool, a: &[FheUint32], b: &[FheUint32]) -> Vec<FheUint32> {
+        let len = std::cmp::max(a.len(), b.len());
+        let zero = FheUint32::encrypt_trivial(0u32);
+        (0..len)
+            .map(|i| cond.if_then_else(a.get(i).unwrap_or(&zero), b.get(i).unwrap_or(&zero)))
+            .collect()
+    }
+
+    /// 3:2 carry-save adder: returns two values that add up to `x + y + z`.
+    ///
+    /// Every digit position is summed on its own into a `FheUint64` below 3 * 2^32, whose lower
+    /// half goes to the sum and whose upper half (at most 2) goes one position up in the carries.
+    fn compress_3_2(x: &Self, y: &Self, z: &Self) -> (Self, Self) {
+        let len = [x.digits.len(), y.digits.len(), z.digits.len()].into_iter().max().unwrap_or(0);
+        let mut sums = Vec::with_capacity(len);
+        let mut carries = Vec::with_capacity(len + 1);
+        carries.push(FheUint32::encrypt_trivial(0u32));
+
+        for i in 0..len {
+            let terms: Vec<&FheUint32> = [&x.digits, &y.digits, &z.digits].into_iter()
+                .filter_map(|digits| digits.get(i))
+                .collect();
+            if let [digit] = terms.as_slice() {
+                // A lone digit can't carry
+                sums.push((*digit).clone());
+                carries.push(FheUint32::encrypt_trivial(0u32));
+                continue;
+            }
+
+            let total = terms.into_iter()
+                .map(|digit| FheUint64::cast_from(digit.clone()))
+                .reduce(|acc, term| acc + term)
+                .unwrap();
+            sums.push(BigUintFHE::extract_lower_bits(&total));
+            carries.push(BigUintFHE::extract_upper_bits(&total));
+        }
+
+        // Both parts are at most the whole sum
+        let bound = &x.bound + &y.bound + &z.bound;
+        (BigUintFHE::from_parts(sums, bound.clone()), BigUintFHE::from_parts(carries, bound))
+    }
+
+    /// Returns an id no other value has
+    fn next_id() -> u64 {
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Number of digits needed to hold every value up to `bound`
+    pub(crate) fn digit_count(bound: &BigUint) -> usize {
+        bound.bits().div_ceil(32) as usize
+    }
+
+    /// Largest value `len` digits can hold, 2^(32 * len) - 1
+    pub(crate) fn max_value(len: usize) -> BigUint {
+        (BigUint::from(1u32) << (32 * len)) - 1u32
+    }
+
     /// Extract upper 32 bits from a sum
     fn extract_upper_bits(sum: &FheUint64) -> FheUint32 {
         // Right shift by 32 bits to get the upper bits
@@ -120,147 +1053,83 @@ impl BigUintFHE {
 impl Add for BigUintFHE {
     type Output = Self;
 
+    /// Ripple-carry addition. The result has one digit more than the longer operand only if the
+    /// sum of the bounds needs it, otherwise the top carry is provably zero and never computed.
     fn add(self, other: Self) -> Self {
-        let mut result = Vec::new();
-        let max_len = std::cmp::max(self.digits.len(), other.digits.len());
-        let mut carry: Option<FheUint32> = None;
-
-        for i in 0..max_len {
-            let a = if i < self.digits.len() { Some(&self.digits[i]) } else { None };
-            let b = if i < other.digits.len() { Some(&other.digits[i]) } else { None };
-
-            let sum = match (a, b, carry.as_ref()) {
-                (Some(a), Some(b), Some(c)) => {
+        let bound = &self.bound + &other.bound;
+        let out_len = BigUintFHE::digit_count(&bound);
+        let mut result = Vec::with_capacity(out_len);
+        let mut carry: Option<FheUint64> = None;
+
+        for i in 0..out_len {
+            let sum = match (self.digits.get(i), other.digits.get(i), carry.take()) {
+                (Some(a), Some(b), c) => {
                     // Convert to u64 for the sum
-                    let a64 = FheUint64::cast_from(a.clone());
-                    let b64 = FheUint64::cast_from(b.clone());
-                    let c64 = FheUint64::cast_from(c.clone());
-                    let temp_sum = a64 + b64 + c64;
-
-                    // Extract carry and result
-                    let next_carry = FheUint32::cast_from(&temp_sum >> 32u64);
-                    carry = Some(next_carry);
-                    FheUint32::cast_from(&temp_sum & 0xFFFFFFFFu64)
-                },
-                (Some(a), Some(b), None) => {
-                    let a64 = FheUint64::cast_from(a.clone());
-                    let b64 = FheUint64::cast_from(b.clone());
-                    let temp_sum = a64 + b64;
-
-                    let next_carry = FheUint32::cast_from(&temp_sum >> 32u64);
-                    carry = Some(next_carry);
-                    FheUint32::cast_from(&temp_sum & 0xFFFFFFFFu64)
-                },
-                (Some(a), None, Some(c)) => {
-                    let a64 = FheUint64::cast_from(a.clone());
-                    let c64 = FheUint64::cast_from(c.clone());
-                    let temp_sum = a64 + c64;
-
-                    let next_carry = FheUint32::cast_from(&temp_sum >> 32u64);
-                    carry = Some(next_carry);
-                    FheUint32::cast_from(&temp_sum & 0xFFFFFFFFu64)
-                },
-                (Some(a), None, None) => {
-                    a.clone()
+                    let mut temp_sum = FheUint64::cast_from(a.clone()) + FheUint64::cast_from(b.clone());
+                    if let Some(c) = c {
+                        temp_sum += c;
+                    }
+                    temp_sum
                 },
-                (None, Some(b), Some(c)) => {
-                    let b64 = FheUint64::cast_from(b.clone());
-                    let c64 = FheUint64::cast_from(c.clone());
-                    let temp_sum = b64 + c64;
-
-                    let next_carry = FheUint32::cast_from(&temp_sum >> 32u64);
-                    carry = Some(next_carry);
-                    FheUint32::cast_from(&temp_sum & 0xFFFFFFFFu64)
-                },
-                (None, Some(b), None) => {
-                    b.clone()
+                (Some(d), None, Some(c)) | (None, Some(d), Some(c)) => FheUint64::cast_from(d.clone()) + c,
+                (Some(d), None, None) | (None, Some(d), None) => {
+                    result.push(d.clone());
+                    continue;
                 },
                 (None, None, Some(c)) => {
-                    c.clone()
+                    result.push(FheUint32::cast_from(c));
+                    continue;
+                },
+                (None, None, None) => {
+                    result.push(FheUint32::encrypt_trivial(0u32));
+                    continue;
                 },
-                (None, None, None) => break,
             };
-            result.push(sum);
-        }
 
-        if let Some(c) = carry {
-            result.push(c);
+            // Extract carry and result
+            result.push(BigUintFHE::extract_lower_bits(&sum));
+            if i + 1 < out_len {
+                carry = Some(sum >> 32u64);
+            }
         }
 
-        Self { digits: result, client_key: self.client_key.clone() }
+        BigUintFHE::from_parts(result, bound)
+    }
+}
+
+impl Sub for BigUintFHE {
+    type Output = Self;
+
+    /// Wrapping subtraction, see `overflowing_sub` to also get the borrow.
+    fn sub(self, other: Self) -> Self {
+        self.overflowing_sub(&other).0
     }
 }
 
 impl Mul for BigUintFHE {
     type Output = Self;
 
+    /// The result has as many digits as the product of the bounds needs, at most `n + m`.
     fn mul(self, other: Self) -> Self {
-        if self.digits.is_empty() || other.digits.len() == 0 {
-            return Self { digits: Vec::new(), client_key: self.client_key.clone() };
+        // x * x, e.g. from `mul_mod(x, x)`, needs only half the digit products
+        if self.id == other.id {
+            return self.square();
         }
 
-        let start_total = Instant::now();
-
-        // Initialize result vector with zeros
-        let start_init = Instant::now();
-        let mut result = vec![
-            FheUint32::try_encrypt(0u32, &self.client_key).unwrap();
-            self.digits.len() + other.digits.len()
-        ];
-        println!("Init time: {:?}", start_init.elapsed());
-
-        // Compute each partial product and add to the appropriate position
-        let start_products = Instant::now();
-        for (i, a) in self.digits.iter().enumerate() {
-            for (j, b) in other.digits.iter().enumerate() {
-                let start_iter = Instant::now();
-                let idx = i + j;
-
-                let start_mul = Instant::now();
-                // Convert to FheUint64 for multiplication
-                let a64 = FheUint64::cast_from(a.clone());
-                let b64 = FheUint64::cast_from(b.clone());
-                let product = a64 * b64;
-                println!("Multiplication time: {:?}", start_mul.elapsed());
-
-                // Split product into lower and upper 32 bits
-                let start_split = Instant::now();
-                let lower = BigUintFHE::extract_lower_bits(&product);
-                let upper = BigUintFHE::extract_upper_bits(&product);
-                println!("Split time: {:?}", start_split.elapsed());
-
-                let start_add = Instant::now();
-                // Add lower part and handle potential carry
-                let current_pos = FheUint64::cast_from(result[idx].clone());
-                let lower64 = FheUint64::cast_from(lower);
-                let sum = current_pos + lower64;
-                result[idx] = BigUintFHE::extract_lower_bits(&sum);
-
-                // Add upper part plus any carry from lower addition
-                let next_pos = FheUint64::cast_from(result[idx + 1].clone());
-                let upper64 = FheUint64::cast_from(upper);
-                let carry64 = FheUint64::cast_from(BigUintFHE::extract_upper_bits(&sum));
-                let sum = next_pos + upper64 + carry64;
-                result[idx + 1] = BigUintFHE::extract_lower_bits(&sum);
-
-                // Handle potential new carry
-                if idx + 2 < result.len() {
-                    result[idx + 2] = result[idx + 2].clone() + BigUintFHE::extract_upper_bits(&sum);
-                }
-                println!("Addition time: {:?}", start_add.elapsed());
-
-                println!("Total iteration time for {:?} / {:?}: {:?}", i + j + 1, result.len() - 1, start_iter.elapsed());
-            }
+        let bound = &self.bound * &other.bound;
+        if bound == BigUint::from(0u32) {
+            return Self::zero();
         }
-        println!("Total products time: {:?}", start_products.elapsed());
 
-        let result = Self {
-            digits: result,
-            client_key: self.client_key.clone()
-        };
+        // Large operands are cheaper with Karatsuba
+        if std::cmp::min(self.digits.len(), other.digits.len()) >= KARATSUBA_THRESHOLD {
+            return self.karatsuba_mul(&other, KARATSUBA_THRESHOLD);
+        }
 
-        println!("Total multiplication time: {:?}", start_total.elapsed());
-        result
+        // Collect every partial product in wide columns and resolve the carries once
+        let mut acc = CarrySaveAccumulator::new(BigUintFHE::digit_count(&bound));
+        acc.add_product(&self, &other);
+        acc.normalize().with_bound(&bound)
     }
 }
 
@@ -425,6 +1294,459 @@ mod tests {
         assert_eq!(product.to_biguint(&client_key), a * b);
     }
 
+    #[test]
+    fn test_server_side_arithmetic() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+
+        // Client side: encrypt the secret input
+        let a = BigUint::from(0xFFFFFFFFu32);
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+
+        // Server side: only the server key is available on this thread
+        let result = std::thread::spawn(move || {
+            tfhe::set_server_key(server_key);
+            let sum = a_enc + BigUintFHE::one();
+            sum.mul_clear(&BigUint::from(3u32)).add_clear(&BigUint::from(5u32))
+        }).join().unwrap();
+
+        // Client side: decrypt the result
+        assert_eq!(result.to_biguint(&client_key), (a + 1u32) * 3u32 + 5u32);
+    }
+
+    #[test]
+    fn test_public_key_encryption() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let a = BigUint::from(0x1_FFFFFFFFu64);
+        let b = BigUint::from(12345u32);
+        let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+
+        // A device holding only the compact public key encrypts and sends the list
+        let compact_key = CompactPublicKey::new(&client_key);
+        let list = BigUintFHE::encrypt_compact(&a, &compact_key);
+        let a_enc = BigUintFHE::from_compact_list(&list).unwrap();
+        assert_eq!(a_enc.to_biguint(&client_key), a);
+        assert_eq!((a_enc + b_enc.clone()).to_biguint(&client_key), &a + &b);
+
+        // Encryption under the classic public key
+        let public_key = PublicKey::new(&client_key);
+        let a_enc = BigUintFHE::new_with_public_key(a.clone(), &public_key).unwrap();
+        assert_eq!((a_enc + b_enc).to_biguint(&client_key), &a + &b);
+    }
+
+    #[test]
+    fn test_add_clear() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        // Carry ripples through the first digit into a longer clear operand
+        let a = BigUint::from(0xFFFFFFFFu32);
+        let b = BigUint::parse_bytes(b"1FFFFFFFF00000001", 16).unwrap();
+
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let sum = a_enc.add_clear(&b);
+        assert_eq!(sum.to_biguint(&client_key), &a + &b);
+
+        // Adding zero keeps the value untouched
+        let sum = a_enc.add_clear(&BigUint::from(0u32));
+        assert_eq!(sum.to_biguint(&client_key), a);
+    }
+
+    #[test]
+    fn test_mul_clear() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let a = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFF", 16).unwrap();
+        // The zero low digit of the clear operand is skipped
+        let b = BigUint::parse_bytes(b"FFFFFFFF00000000", 16).unwrap();
+
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let product = a_enc.mul_clear(&b);
+        assert_eq!(product.digits.len(), 4);
+        assert_eq!(product.to_biguint(&client_key), &a * &b);
+
+        let product = a_enc.mul_clear(&BigUint::from(0u32));
+        assert_eq!(product.to_biguint(&client_key), BigUint::from(0u32));
+    }
+
+    #[test]
+    fn test_mul_add() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let d = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFF", 16).unwrap();
+        let e = BigUint::parse_bytes(b"FFFFFFFF00000001", 16).unwrap();
+        let k = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF", 16).unwrap();
+        let d_enc = BigUintFHE::new(d.clone(), &client_key).unwrap();
+        let e_enc = BigUintFHE::new(e.clone(), &client_key).unwrap();
+        let k_enc = BigUintFHE::new(k.clone(), &client_key).unwrap();
+        let expected = &k + &e * &d;
+
+        // Every combination of clear and encrypted operands
+        assert_eq!(d_enc.mul_add(&e, &k).to_biguint(&client_key), expected);
+        assert_eq!(d_enc.mul_add(&e_enc, &k).to_biguint(&client_key), expected);
+        assert_eq!(d_enc.mul_add(&e, &k_enc).to_biguint(&client_key), expected);
+
+        // Same value as the separate Mul and Add
+        let fused = d_enc.mul_add(&e_enc, &k_enc);
+        let separate = k_enc + e_enc * d_enc;
+        assert_eq!(fused.to_biguint(&client_key), separate.to_biguint(&client_key));
+    }
+
+    #[test]
+    fn test_square() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        // Maximal digits make every doubled cross product carry
+        let a = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFF", 16).unwrap();
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let square = a_enc.square();
+        assert_eq!(square.digits.len(), 6);
+        assert_eq!(square.to_biguint(&client_key), &a * &a);
+
+        // Clones are squared by Mul, distinct encryptions of the same value are multiplied
+        let same = a_enc.clone() * a_enc.clone();
+        assert_eq!(same.to_biguint(&client_key), &a * &a);
+        let other = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        assert_eq!((a_enc * other).to_biguint(&client_key), &a * &a);
+
+        assert_eq!(BigUintFHE::zero().square().to_biguint(&client_key), BigUint::from(0u32));
+    }
+
+    #[test]
+    fn test_par_mul() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key.clone());
+
+        let a = BigUint::from(0xFFFFFFFF_FFFFFFFFu64);
+        let b = BigUint::from(0xFFFFFFFF_00000001u64);
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+
+        let parallel = a_enc.par_mul(&b_enc, &server_key);
+        let serial = a_enc * b_enc;
+        assert_eq!(parallel.digits().len(), serial.digits().len());
+        assert_eq!(parallel.to_biguint(&client_key), &a * &b);
+        assert_eq!(serial.to_biguint(&client_key), &a * &b);
+    }
+
+    #[test]
+    fn test_par_add() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key.clone());
+
+        // The carry out of the lowest digit has to travel through every other digit
+        let a = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF", 16).unwrap();
+        let b = BigUint::from(1u32);
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+
+        let parallel = a_enc.par_add(&b_enc, &server_key);
+        let serial = a_enc.clone() + b_enc;
+        assert_eq!(parallel.digits.len(), serial.digits.len());
+        assert_eq!(parallel.to_biguint(&client_key), &a + &b);
+
+        // Carries generated and propagated in the middle digits
+        let c = BigUint::parse_bytes(b"12345678FFFFFFFF80000000", 16).unwrap();
+        let c_enc = BigUintFHE::new(c.clone(), &client_key).unwrap();
+        let sum = c_enc.par_add(&a_enc, &server_key);
+        assert_eq!(sum.to_biguint(&client_key), &a + &c);
+    }
+
+    #[test]
+    fn test_sum() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key.clone());
+
+        // Operands of different lengths, with maximal digits so every level carries
+        let values = [
+            BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFF", 16).unwrap(),
+            BigUint::parse_bytes(b"FFFFFFFFFFFFFFFF", 16).unwrap(),
+            BigUint::from(u32::MAX),
+            BigUint::parse_bytes(b"123456789ABCDEF0123", 16).unwrap(),
+            BigUint::from(0u32),
+            BigUint::parse_bytes(b"FFFFFFFF00000001", 16).unwrap(),
+            BigUint::from(1u32),
+        ];
+        let encrypted: Vec<BigUintFHE> = values.iter()
+            .map(|v| BigUintFHE::new(v.clone(), &client_key).unwrap())
+            .collect();
+        let expected: BigUint = values.iter().sum();
+
+        let sum = BigUintFHE::sum(encrypted.clone(), &server_key);
+        assert_eq!(sum.to_biguint(&client_key), expected);
+        let folded = encrypted.into_iter().reduce(|acc, v| acc + v).unwrap();
+        assert_eq!(sum.digits.len(), folded.digits.len());
+
+        assert_eq!(BigUintFHE::sum(Vec::new(), &server_key).to_biguint(&client_key), BigUint::from(0u32));
+        let single = BigUintFHE::from_u32(42u32, &client_key).unwrap();
+        assert_eq!(BigUintFHE::sum([single], &server_key).to_biguint(&client_key), BigUint::from(42u32));
+    }
+
+    #[test]
+    fn test_karatsuba_mul() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let a = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF", 16).unwrap();
+        let b: BigUint = (BigUint::from(1u32) << 288) - BigUint::from(3u32);
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+
+        // Balanced split with maximal carries in the middle term
+        let square = a_enc.karatsuba_mul(&a_enc, 4);
+        assert_eq!(square.digits.len(), 8);
+        assert_eq!(square.to_biguint(&client_key), &a * &a);
+
+        // Unbalanced operands and an odd digit count
+        let product = a_enc.karatsuba_mul(&b_enc, 4);
+        assert_eq!(product.digits.len(), 13);
+        assert_eq!(product.to_biguint(&client_key), &a * &b);
+    }
+
+    #[test]
+    fn test_sub_with_borrow() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        // Longer minuend: the borrow ripples out of the low digit
+        let a = BigUint::parse_bytes(b"100000000", 16).unwrap();
+        let b = BigUint::from(1u32);
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+
+        let (diff, borrow) = a_enc.overflowing_sub(&b_enc);
+        assert_eq!(diff.digits.len(), 2);
+        assert_eq!(diff.to_biguint(&client_key), &a - &b);
+        assert!(!FheDecrypt::<bool>::decrypt(&borrow, &client_key));
+
+        // Longer subtrahend: the result wraps around modulo 2^64
+        let (diff, borrow) = b_enc.overflowing_sub(&a_enc);
+        let wrapped = (BigUint::from(1u32) << 64) + &b - &a;
+        assert_eq!(diff.digits.len(), 2);
+        assert_eq!(diff.to_biguint(&client_key), wrapped);
+        assert!(FheDecrypt::<bool>::decrypt(&borrow, &client_key));
+
+        let diff = a_enc.clone() - a_enc;
+        assert_eq!(diff.to_biguint(&client_key), BigUint::from(0u32));
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let small = BigUint::from(0xFFFFFFFFu32);
+        let large = BigUint::parse_bytes(b"100000000", 16).unwrap();
+        let small_enc = BigUintFHE::new(small.clone(), &client_key).unwrap();
+        let large_enc = BigUintFHE::new(large.clone(), &client_key).unwrap();
+        let decrypt = |b: FheBool| FheDecrypt::<bool>::decrypt(&b, &client_key);
+
+        // Operands with different digit counts
+        assert!(decrypt(small_enc.lt(&large_enc)));
+        assert!(decrypt(small_enc.le(&large_enc)));
+        assert!(!decrypt(small_enc.gt(&large_enc)));
+        assert!(!decrypt(small_enc.ge(&large_enc)));
+        assert!(!decrypt(small_enc.eq(&large_enc)));
+        assert!(decrypt(large_enc.gt(&small_enc)));
+
+        // Equal values, one of them carrying a zero top digit
+        let three = BigUintFHE::from_u32(1u32, &client_key).unwrap() + BigUintFHE::from_u32(2u32, &client_key).unwrap();
+        let three_short = BigUintFHE::from_u32(3u32, &client_key).unwrap();
+        assert_eq!(three.digits.len(), 2);
+        assert!(decrypt(three.eq(&three_short)));
+        assert!(decrypt(three.le(&three_short)));
+        assert!(!decrypt(three.lt(&three_short)));
+
+        // Plaintext right-hand sides
+        assert!(decrypt(small_enc.lt_clear(&large)));
+        assert!(decrypt(large_enc.ge_clear(&large)));
+        assert!(decrypt(large_enc.eq_clear(&large)));
+        assert!(!decrypt(large_enc.le_clear(&small)));
+        assert!(decrypt(small_enc.gt_clear(&BigUint::from(0u32))));
+
+        assert!(!decrypt(small_enc.is_zero()));
+        assert!(decrypt(BigUintFHE::zero().is_zero()));
+    }
+
+    #[test]
+    fn test_select() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let a = BigUint::parse_bytes(b"123456789ABCDEF", 16).unwrap();
+        let b = BigUint::from(42u32);
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+        let yes = FheBool::encrypt(true, &client_key);
+        let no = FheBool::encrypt(false, &client_key);
+
+        // Inputs of unequal length
+        let picked = BigUintFHE::select(&yes, &a_enc, &b_enc);
+        assert_eq!(picked.digits.len(), 2);
+        assert_eq!(picked.to_biguint(&client_key), a);
+        let picked = BigUintFHE::select(&no, &a_enc, &b_enc);
+        assert_eq!(picked.to_biguint(&client_key), b);
+
+        let mut value = b_enc.clone();
+        value.conditional_assign(&no, &a_enc);
+        assert_eq!(value.to_biguint(&client_key), b);
+        value.conditional_assign(&yes, &a_enc);
+        assert_eq!(value.to_biguint(&client_key), a);
+    }
+
+    #[test]
+    fn test_reduce_mod() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let modulus = BigUint::from(1000003u32);
+        let x = BigUint::parse_bytes(b"123456789ABCDEF", 16).unwrap();
+
+        let x_enc = BigUintFHE::new(x.clone(), &client_key).unwrap();
+        let reduced = x_enc.reduce_mod(&modulus);
+        assert_eq!(reduced.digits.len(), 1);
+        assert_eq!(reduced.to_biguint(&client_key), &x % &modulus);
+
+        // Values provably below the modulus are returned as is
+        let wide_modulus = BigUint::parse_bytes(b"10000000000000061", 16).unwrap();
+        let small = BigUintFHE::from_u32(42u32, &client_key).unwrap();
+        let reduced = small.reduce_mod(&wide_modulus);
+        assert_eq!(reduced.digits.len(), 1);
+        assert_eq!(reduced.to_biguint(&client_key), BigUint::from(42u32));
+    }
+
+    #[test]
+    fn test_bound_tracking() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        // Digits above a declared bound are dropped
+        let digits = vec![FheUint32::encrypt(7u32, &client_key), FheUint32::encrypt(0u32, &client_key)];
+        let seven = BigUintFHE::from_encrypted_digits(digits);
+        assert_eq!(seven.bound(), &BigUint::from(u64::MAX));
+        let seven = seven.with_bound(&BigUint::from(7u32));
+        assert_eq!(seven.digits.len(), 1);
+        assert_eq!(seven.to_biguint(&client_key), BigUint::from(7u32));
+
+        // No carry digit when the bounds rule it out
+        let sum = seven.clone() + BigUintFHE::trivial(&BigUint::from(5u32));
+        assert_eq!(sum.bound(), &BigUint::from(12u32));
+        assert_eq!(sum.digits.len(), 1);
+        assert_eq!(sum.to_biguint(&client_key), BigUint::from(12u32));
+        assert_eq!(seven.mul_clear(&BigUint::from(1000u32)).digits.len(), 1);
+
+        // k + e * d with d, e, k < n fits in 16 digits instead of 17
+        let n = crate::scalar::get_curve_order();
+        let d = &n - BigUint::from(3u32);
+        let e = &n - BigUint::from(1u32);
+        let k = &n - BigUint::from(2u32);
+        let d_enc = BigUintFHE::new(d.clone(), &client_key).unwrap().with_bound(&(&n - 1u32));
+        let s = d_enc.mul_add(&e, &k);
+        assert_eq!(s.digits.len(), 16);
+        let reduced = s.reduce_mod(&n);
+        assert_eq!(reduced.bound(), &(&n - 1u32));
+        assert_eq!(reduced.to_biguint(&client_key), (&k + &e * &d) % &n);
+    }
+
+    #[test]
+    fn test_div_rem_clear() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let n = crate::scalar::get_curve_order();
+        let max = (BigUint::from(1u32) << 512) - BigUint::from(1u32);
+        let product = (&n - BigUint::from(1u32)) * (&n - BigUint::from(2u32)) + BigUint::from(12345u32);
+
+        for x in [max, product] {
+            let x_enc = BigUintFHE::new(x.clone(), &client_key).unwrap();
+            assert_eq!(x_enc.digits.len(), 16);
+            let (q, r) = x_enc.div_rem_clear(&n);
+            assert_eq!(q.digits.len(), 9);
+            assert_eq!(r.digits.len(), 8);
+            assert_eq!(q.to_biguint(&client_key), &x / &n);
+            assert_eq!(r.to_biguint(&client_key), &x % &n);
+        }
+
+        // Dividends shorter than the divisor give a zero quotient
+        let small = BigUintFHE::from_u32(42u32, &client_key).unwrap();
+        let (q, r) = small.div_rem_clear(&n);
+        assert_eq!(q.to_biguint(&client_key), BigUint::from(0u32));
+        assert_eq!(r.to_biguint(&client_key), BigUint::from(42u32));
+    }
+
+    #[test]
+    fn test_add_mod_and_mul_mod() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let modulus = BigUint::from(65521u32);
+        let a = BigUint::from(65000u32);
+        let b = BigUint::from(60000u32);
+
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+
+        let sum = a_enc.add_mod(&b_enc, &modulus);
+        assert_eq!(sum.to_biguint(&client_key), (&a + &b) % &modulus);
+
+        let product = a_enc.mul_mod(&b_enc, &modulus);
+        assert_eq!(product.to_biguint(&client_key), (&a * &b) % &modulus);
+    }
+
+    #[test]
+    fn test_pow_mod_and_inverse_mod() {
+        use crate::field::FieldElement;
+        use num_bigint::RandBigInt;
+
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        // 2^32 + 15 is prime and needs two digits
+        let p = (BigUint::from(1u32) << 32) + BigUint::from(15u32);
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..2 {
+            let x = rng.gen_biguint_range(&BigUint::from(1u32), &p);
+            // A short exponent keeps pow_mod_clear to a few windows
+            let exp = rng.gen_biguint(12);
+            let x_enc = BigUintFHE::new(x.clone(), &client_key).unwrap();
+            let expected = FieldElement::new(x.clone(), p.clone());
+
+            let power = x_enc.pow_mod_clear(&exp, &p);
+            assert_eq!(power.digits.len(), 2);
+            assert_eq!(&power.to_biguint(&client_key), expected.pow(&exp).value());
+
+            let inverse = x_enc.inverse_mod(&p);
+            assert_eq!(&inverse.to_biguint(&client_key), expected.inverse().value());
+        }
+
+        // A zero exponent gives one
+        let x_enc = BigUintFHE::from_u32(5u32, &client_key).unwrap();
+        assert_eq!(x_enc.pow_mod_clear(&BigUint::from(0u32), &p).to_biguint(&client_key), BigUint::from(1u32));
+    }
+
     #[test]
     fn test_extract_carry_and_lower_bits() {
         let config = ConfigBuilder::default().build();
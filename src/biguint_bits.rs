@@ -0,0 +1,332 @@
+//! Shifts, bitwise operations and bit extraction on `BigUintFHE`.
+//!
+//! Shifts by clear amounts move whole digits for free and only shift the digits themselves by
+//! the remaining `amount % 32` bits. Shifts by encrypted amounts use a barrel shifter: one
+//! clear shift per bit of the amount, each kept or discarded with an oblivious select.
+
+use std::ops::{BitAnd, BitOr, BitXor, Shl, Shr};
+use tfhe::prelude::*;
+use tfhe::{FheBool, FheUint32};
+use num_bigint::BigUint;
+use crate::biguint::BigUintFHE;
+
+impl BigUintFHE {
+    /// Shifts left by a clear amount. The result grows by as many digits as the shifted bound
+    /// needs, so no bits are lost.
+    pub fn shl_clear(&self, amount: usize) -> Self {
+        let bound = self.bound() << amount;
+        let out_len = BigUintFHE::digit_count(&bound);
+        BigUintFHE::from_encrypted_digits(shl_digits(self.digits(), amount, out_len)).with_bound(&bound)
+    }
+
+    /// Shifts right by a clear amount, dropping the digits that are shifted out entirely
+    pub fn shr_clear(&self, amount: usize) -> Self {
+        let bound = self.bound() >> amount;
+        let out_len = BigUintFHE::digit_count(&bound);
+        BigUintFHE::from_encrypted_digits(shr_digits(self.digits(), amount, out_len)).with_bound(&bound)
+    }
+
+    /// Shifts left by an encrypted amount, keeping the current number of digits.
+    ///
+    /// Bits shifted past the top digit are lost, and amounts of at least `32 * digits` give zero.
+    pub fn shl_encrypted(&self, amount: &FheUint32) -> Self {
+        let len = self.digits().len();
+        self.barrel_shift(amount, |digits, shift| shl_digits(digits, shift, len))
+    }
+
+    /// Shifts right by an encrypted amount, keeping the current number of digits.
+    ///
+    /// Amounts of at least `32 * digits` give zero.
+    pub fn shr_encrypted(&self, amount: &FheUint32) -> Self {
+        let len = self.digits().len();
+        self.barrel_shift(amount, |digits, shift| shr_digits(digits, shift, len))
+            .with_bound(self.bound())
+    }
+
+    /// Returns every bit as an encrypted boolean, least significant first
+    pub fn to_bits(&self) -> Vec<FheBool> {
+        self.digits().iter()
+            .flat_map(|digit| (0..32).map(move |k| (digit & (1u32 << k)).ne(0u32)))
+            .collect()
+    }
+
+    /// Returns bit `i` as an encrypted boolean. Bits beyond the digits are a trivial false.
+    pub fn bit(&self, i: usize) -> FheBool {
+        match self.digits().get(i / 32) {
+            Some(digit) => (digit & (1u32 << (i % 32))).ne(0u32),
+            None => FheBool::encrypt_trivial(false),
+        }
+    }
+
+    /// Computes `self & other` for a plaintext `other`. The result has as many digits as the
+    /// shorter operand, and digits masked by a clear zero or all-ones need no encrypted operation.
+    pub fn bitand_clear(&self, other: &BigUint) -> Self {
+        let clear_digits = other.to_u32_digits();
+        let digits = self.digits().iter()
+            .zip(clear_digits)
+            .map(|(digit, c)| match c {
+                0 => FheUint32::encrypt_trivial(0u32),
+                u32::MAX => digit.clone(),
+                c => digit & c,
+            })
+            .collect();
+        BigUintFHE::from_encrypted_digits(digits).with_bound(std::cmp::min(self.bound(), other))
+    }
+
+    /// Computes `self | other` for a plaintext `other`. The result has as many digits as the
+    /// longer operand.
+    pub fn bitor_clear(&self, other: &BigUint) -> Self {
+        BigUintFHE::from_encrypted_digits(clear_digit_op(self.digits(), other, |digit, c| digit | c))
+            .with_bound(&bit_mask_bound(self.bound(), other))
+    }
+
+    /// Computes `self ^ other` for a plaintext `other`. The result has as many digits as the
+    /// longer operand.
+    pub fn bitxor_clear(&self, other: &BigUint) -> Self {
+        BigUintFHE::from_encrypted_digits(clear_digit_op(self.digits(), other, |digit, c| digit ^ c))
+            .with_bound(&bit_mask_bound(self.bound(), other))
+    }
+
+    /// Applies `shift(digits, 2^k)` for every set bit `k` of the encrypted amount
+    fn barrel_shift(&self, amount: &FheUint32, shift: impl Fn(&[FheUint32], usize) -> Vec<FheUint32>) -> Self {
+        let width = 32 * self.digits().len();
+        if width == 0 {
+            return self.clone();
+        }
+
+        let mut digits = self.digits().to_vec();
+        let mut step = 1;
+        while step < width {
+            let bit_set = (amount & step as u32).ne(0u32);
+            let shifted = shift(&digits, step);
+            digits = BigUintFHE::select_digits(&bit_set, &shifted, &digits);
+            step *= 2;
+        }
+
+        // Larger amounts shift every bit out
+        let overflow = amount.ge(width as u32);
+        let zero = vec![FheUint32::encrypt_trivial(0u32); digits.len()];
+        BigUintFHE::from_encrypted_digits(BigUintFHE::select_digits(&overflow, &zero, &digits))
+    }
+}
+
+/// Bound of `a | b` and `a ^ b`: every bit up to the highest one either operand may have
+fn bit_mask_bound(a: &BigUint, b: &BigUint) -> BigUint {
+    (BigUint::from(1u32) << std::cmp::max(a.bits(), b.bits())) - 1u32
+}
+
+/// Shifts digits left by `amount` bits, keeping the lowest `out_len` digits
+fn shl_digits(a: &[FheUint32], amount: usize, out_len: usize) -> Vec<FheUint32> {
+    let (words, bits) = (amount / 32, (amount % 32) as u32);
+    (0..out_len)
+        .map(|i| {
+            // Digit i takes the low bits of a[i - words] and the high bits of a[i - words - 1]
+            let low = i.checked_sub(words).and_then(|j| a.get(j));
+            let high = i.checked_sub(words + 1).and_then(|j| a.get(j));
+            match (low, high, bits) {
+                (Some(low), _, 0) => low.clone(),
+                (Some(low), Some(high), _) => (low << bits) | (high >> (32 - bits)),
+                (Some(low), None, _) => low << bits,
+                (None, Some(high), b) if b != 0 => high >> (32 - bits),
+                _ => FheUint32::encrypt_trivial(0u32),
+            }
+        })
+        .collect()
+}
+
+/// Shifts digits right by `amount` bits, keeping the lowest `out_len` digits
+fn shr_digits(a: &[FheUint32], amount: usize, out_len: usize) -> Vec<FheUint32> {
+    let (words, bits) = (amount / 32, (amount % 32) as u32);
+    (0..out_len)
+        .map(|i| {
+            // Digit i takes the high bits of a[i + words] and the low bits of a[i + words + 1]
+            let low = a.get(i + words);
+            let high = a.get(i + words + 1);
+            match (low, high, bits) {
+                (Some(low), _, 0) => low.clone(),
+                (Some(low), Some(high), _) => (low >> bits) | (high << (32 - bits)),
+                (Some(low), None, _) => low >> bits,
+                _ => FheUint32::encrypt_trivial(0u32),
+            }
+        })
+        .collect()
+}
+
+/// Combines encrypted digits with the digits of a clear value over the longer of the two.
+/// Clear zero digits leave the encrypted digit untouched, and digits only the clear value has
+/// are encrypted trivially, which is correct for `|` and `^`.
+fn clear_digit_op(a: &[FheUint32], b: &BigUint, op: impl Fn(&FheUint32, u32) -> FheUint32) -> Vec<FheUint32> {
+    let clear_digits = b.to_u32_digits();
+    let len = std::cmp::max(a.len(), clear_digits.len());
+    (0..len)
+        .map(|i| {
+            let c = clear_digits.get(i).copied().unwrap_or(0);
+            match a.get(i) {
+                Some(digit) if c == 0 => digit.clone(),
+                Some(digit) => op(digit, c),
+                None => FheUint32::encrypt_trivial(c),
+            }
+        })
+        .collect()
+}
+
+/// Combines the digits of two encrypted values over the longer of the two. A digit only one
+/// operand has is kept as is, which is correct for `|` and `^`.
+fn digit_op(a: &[FheUint32], b: &[FheUint32], op: impl Fn(&FheUint32, &FheUint32) -> FheUint32) -> Vec<FheUint32> {
+    let len = std::cmp::max(a.len(), b.len());
+    (0..len)
+        .map(|i| match (a.get(i), b.get(i)) {
+            (Some(x), Some(y)) => op(x, y),
+            (Some(d), None) | (None, Some(d)) => d.clone(),
+            (None, None) => unreachable!(),
+        })
+        .collect()
+}
+
+impl Shl<usize> for BigUintFHE {
+    type Output = Self;
+
+    /// Growing left shift, see `shl_clear`.
+    fn shl(self, amount: usize) -> Self {
+        self.shl_clear(amount)
+    }
+}
+
+impl Shr<usize> for BigUintFHE {
+    type Output = Self;
+
+    /// Right shift, see `shr_clear`.
+    fn shr(self, amount: usize) -> Self {
+        self.shr_clear(amount)
+    }
+}
+
+impl Shl<&FheUint32> for BigUintFHE {
+    type Output = Self;
+
+    /// Fixed-width left shift, see `shl_encrypted`.
+    fn shl(self, amount: &FheUint32) -> Self {
+        self.shl_encrypted(amount)
+    }
+}
+
+impl Shr<&FheUint32> for BigUintFHE {
+    type Output = Self;
+
+    /// Fixed-width right shift, see `shr_encrypted`.
+    fn shr(self, amount: &FheUint32) -> Self {
+        self.shr_encrypted(amount)
+    }
+}
+
+impl BitAnd for BigUintFHE {
+    type Output = Self;
+
+    /// The result has as many digits as the shorter operand.
+    fn bitand(self, other: Self) -> Self {
+        let digits = self.digits().iter()
+            .zip(other.digits())
+            .map(|(a, b)| a & b)
+            .collect();
+        BigUintFHE::from_encrypted_digits(digits).with_bound(std::cmp::min(self.bound(), other.bound()))
+    }
+}
+
+impl BitOr for BigUintFHE {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        BigUintFHE::from_encrypted_digits(digit_op(self.digits(), other.digits(), |a, b| a | b))
+            .with_bound(&bit_mask_bound(self.bound(), other.bound()))
+    }
+}
+
+impl BitXor for BigUintFHE {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self {
+        BigUintFHE::from_encrypted_digits(digit_op(self.digits(), other.digits(), |a, b| a ^ b))
+            .with_bound(&bit_mask_bound(self.bound(), other.bound()))
+    }
+}
+
+impl BitAnd<BigUint> for BigUintFHE {
+    type Output = Self;
+
+    fn bitand(self, other: BigUint) -> Self {
+        self.bitand_clear(&other)
+    }
+}
+
+impl BitOr<BigUint> for BigUintFHE {
+    type Output = Self;
+
+    fn bitor(self, other: BigUint) -> Self {
+        self.bitor_clear(&other)
+    }
+}
+
+impl BitXor<BigUint> for BigUintFHE {
+    type Output = Self;
+
+    fn bitxor(self, other: BigUint) -> Self {
+        self.bitxor_clear(&other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tfhe::ConfigBuilder;
+
+    #[test]
+    fn test_shifts() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let a = BigUint::parse_bytes(b"80000001F0000001", 16).unwrap();
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+
+        // Clear amounts, within a digit and across digits
+        assert_eq!((a_enc.clone() << 4).to_biguint(&client_key), &a << 4);
+        assert_eq!((a_enc.clone() << 36).to_biguint(&client_key), &a << 36);
+        assert_eq!((a_enc.clone() >> 33).to_biguint(&client_key), &a >> 33);
+        assert_eq!((a_enc.clone() >> 64).to_biguint(&client_key), BigUint::from(0u32));
+
+        // Encrypted amounts keep the width
+        let modulus = BigUint::from(1u32) << 64;
+        let amount = FheUint32::encrypt(36u32, &client_key);
+        assert_eq!((a_enc.clone() << &amount).to_biguint(&client_key), (&a << 36) % &modulus);
+        assert_eq!((a_enc.clone() >> &amount).to_biguint(&client_key), &a >> 36);
+        let too_far = FheUint32::encrypt(64u32, &client_key);
+        assert_eq!(a_enc.shr_encrypted(&too_far).to_biguint(&client_key), BigUint::from(0u32));
+    }
+
+    #[test]
+    fn test_bitwise_and_bits() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let a = BigUint::parse_bytes(b"F0F0F0F0FFFF0000", 16).unwrap();
+        let b = BigUint::parse_bytes(b"123456789", 16).unwrap();
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+
+        assert_eq!((a_enc.clone() & b_enc.clone()).to_biguint(&client_key), &a & &b);
+        assert_eq!((a_enc.clone() | b_enc.clone()).to_biguint(&client_key), &a | &b);
+        assert_eq!((a_enc.clone() ^ b_enc.clone()).to_biguint(&client_key), &a ^ &b);
+        assert_eq!((a_enc.clone() & b.clone()).to_biguint(&client_key), &a & &b);
+        assert_eq!((b_enc.clone() | a.clone()).to_biguint(&client_key), &a | &b);
+        assert_eq!((b_enc ^ a.clone()).to_biguint(&client_key), &a ^ &b);
+
+        let bits = a_enc.to_bits();
+        assert_eq!(bits.len(), 64);
+        for (i, bit) in bits.iter().enumerate() {
+            assert_eq!(FheDecrypt::<bool>::decrypt(bit, &client_key), a.bit(i as u64));
+        }
+        assert!(FheDecrypt::<bool>::decrypt(&a_enc.bit(63), &client_key));
+        assert!(!FheDecrypt::<bool>::decrypt(&a_enc.bit(100), &client_key));
+    }
+}
@@ -0,0 +1,239 @@
+use std::ops::{Add, Mul, Sub};
+use tfhe::prelude::*;
+use tfhe::{FheBool, FheUint32, ClientKey};
+use num_bigint::BigUint;
+use crate::biguint::BigUintFHE;
+
+/// A fixed-width unsigned integer of `LIMBS` encrypted u32 digits.
+///
+/// Unlike `BigUintFHE`, results never grow: `Add`, `Sub` and `Mul` wrap around modulo
+/// 2^(32 * LIMBS), and the `overflowing_*` variants also return an encrypted overflow flag.
+/// The cost of every operation is therefore known from the type alone.
+#[derive(Clone)]
+pub struct FixedBigUintFHE<const LIMBS: usize> {
+    // Least significant digit first
+    digits: [FheUint32; LIMBS],
+}
+
+/// A 256-bit fixed-width encrypted integer, e.g. a secp256k1 scalar
+pub type FixedBigUintFHE256 = FixedBigUintFHE<8>;
+
+impl<const LIMBS: usize> FixedBigUintFHE<LIMBS> {
+    /// Number of bits the value holds
+    pub const BITS: usize = 32 * LIMBS;
+
+    /// Encrypts a BigUint value with the client key. Fails if the value does not fit in `LIMBS` digits.
+    pub fn new(value: BigUint, client_key: &ClientKey) -> Result<Self, tfhe::Error> {
+        let digits = Self::clear_digits(&value)?.into_iter()
+            .map(|d| FheUint32::try_encrypt(d, client_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_vec(digits))
+    }
+
+    /// Creates a trivially encrypted value from a public constant. Fails if it does not fit.
+    pub fn trivial(value: &BigUint) -> Result<Self, tfhe::Error> {
+        let digits = Self::clear_digits(value)?.into_iter()
+            .map(FheUint32::encrypt_trivial)
+            .collect();
+        Ok(Self::from_vec(digits))
+    }
+
+    /// Returns a trivially encrypted zero
+    pub fn zero() -> Self {
+        Self::from_vec(vec![FheUint32::encrypt_trivial(0u32); LIMBS])
+    }
+
+    /// Creates a value from exactly `LIMBS` encrypted digits, least significant first
+    pub fn from_digits(digits: [FheUint32; LIMBS]) -> Self {
+        Self { digits }
+    }
+
+    /// Returns the encrypted digits, least significant digit first
+    pub fn digits(&self) -> &[FheUint32; LIMBS] {
+        &self.digits
+    }
+
+    /// Converts a BigUintFHE, dropping digits above `LIMBS` (i.e. reducing modulo 2^(32 * LIMBS))
+    /// and padding shorter values with zero digits
+    pub fn wrapping_from(value: &BigUintFHE) -> Self {
+        let digits = (0..LIMBS)
+            .map(|i| value.digits().get(i).cloned().unwrap_or_else(|| FheUint32::encrypt_trivial(0u32)))
+            .collect();
+        Self::from_vec(digits)
+    }
+
+    /// Converts into a variable-width BigUintFHE with `LIMBS` digits
+    pub fn to_biguint_fhe(&self) -> BigUintFHE {
+        BigUintFHE::from_encrypted_digits(self.digits.to_vec())
+    }
+
+    /// Decrypts the value to a BigUint
+    pub fn to_biguint(&self, client_key: &ClientKey) -> BigUint {
+        let digits: Vec<u32> = self.digits.iter()
+            .map(|d| d.decrypt(client_key))
+            .collect();
+        BigUint::from_slice(&digits)
+    }
+
+    /// Computes `self + other` modulo 2^(32 * LIMBS) and whether the addition overflowed
+    pub fn overflowing_add(&self, other: &Self) -> (Self, FheBool) {
+        let (digits, carry) = BigUintFHE::add_digits(&self.digits, &other.digits, LIMBS);
+        (Self::from_vec(digits), carry)
+    }
+
+    /// Computes `self + other` modulo 2^(32 * LIMBS)
+    pub fn wrapping_add(&self, other: &Self) -> Self {
+        self.overflowing_add(other).0
+    }
+
+    /// Computes `self - other` modulo 2^(32 * LIMBS) and whether the subtraction borrowed
+    pub fn overflowing_sub(&self, other: &Self) -> (Self, FheBool) {
+        let (digits, borrow) = BigUintFHE::sub_digits(&self.digits, &other.digits, LIMBS);
+        (Self::from_vec(digits), borrow)
+    }
+
+    /// Computes `self - other` modulo 2^(32 * LIMBS)
+    pub fn wrapping_sub(&self, other: &Self) -> Self {
+        self.overflowing_sub(other).0
+    }
+
+    /// Computes `self * other` modulo 2^(32 * LIMBS) and whether the product overflowed.
+    ///
+    /// Detecting the overflow needs the high half of the product, so this costs about twice
+    /// as much as `wrapping_mul`.
+    pub fn overflowing_mul(&self, other: &Self) -> (Self, FheBool) {
+        let mut digits = BigUintFHE::mul_digits(&self.digits, &other.digits, 2 * LIMBS);
+        let overflow = digits.split_off(LIMBS).into_iter()
+            .map(|d| d.ne(0u32))
+            .reduce(|acc, nonzero| acc | nonzero)
+            .unwrap_or_else(|| FheBool::encrypt_trivial(false));
+        (Self::from_vec(digits), overflow)
+    }
+
+    /// Computes `self * other` modulo 2^(32 * LIMBS). Partial products that only affect digits
+    /// above `LIMBS` are never computed.
+    pub fn wrapping_mul(&self, other: &Self) -> Self {
+        Self::from_vec(BigUintFHE::mul_digits(&self.digits, &other.digits, LIMBS))
+    }
+
+    /// Multiplies by a plaintext BigUint modulo 2^(32 * LIMBS)
+    pub fn wrapping_mul_clear(&self, other: &BigUint) -> Self {
+        let clear_digits = other.to_u32_digits();
+        Self::from_vec(BigUintFHE::mul_clear_digits(&self.digits, &clear_digits, LIMBS))
+    }
+
+    /// Splits a clear value into exactly `LIMBS` u32 digits
+    fn clear_digits(value: &BigUint) -> Result<Vec<u32>, tfhe::Error> {
+        let mut digits = value.to_u32_digits();
+        if digits.len() > LIMBS {
+            return Err(format!("Value needs {} digits, more than the {} available", digits.len(), LIMBS).into());
+        }
+        digits.resize(LIMBS, 0);
+        Ok(digits)
+    }
+
+    /// Wraps a vector that is known to hold exactly `LIMBS` digits
+    fn from_vec(digits: Vec<FheUint32>) -> Self {
+        match digits.try_into() {
+            Ok(digits) => Self { digits },
+            Err(_) => unreachable!("digit vector must hold exactly LIMBS digits"),
+        }
+    }
+}
+
+impl<const LIMBS: usize> From<FixedBigUintFHE<LIMBS>> for BigUintFHE {
+    fn from(value: FixedBigUintFHE<LIMBS>) -> Self {
+        BigUintFHE::from_encrypted_digits(value.digits.into())
+    }
+}
+
+impl<const LIMBS: usize> Add for FixedBigUintFHE<LIMBS> {
+    type Output = Self;
+
+    /// Wrapping addition, see `overflowing_add` to also get the carry.
+    fn add(self, other: Self) -> Self {
+        self.wrapping_add(&other)
+    }
+}
+
+impl<const LIMBS: usize> Sub for FixedBigUintFHE<LIMBS> {
+    type Output = Self;
+
+    /// Wrapping subtraction, see `overflowing_sub` to also get the borrow.
+    fn sub(self, other: Self) -> Self {
+        self.wrapping_sub(&other)
+    }
+}
+
+impl<const LIMBS: usize> Mul for FixedBigUintFHE<LIMBS> {
+    type Output = Self;
+
+    /// Wrapping multiplication, see `overflowing_mul` to also get the overflow flag.
+    fn mul(self, other: Self) -> Self {
+        self.wrapping_mul(&other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tfhe::ConfigBuilder;
+
+    #[test]
+    fn test_fixed_add_sub() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let modulus = BigUint::from(1u32) << 64u32;
+        let a = BigUint::from(0xFFFFFFFF_FFFFFFF0u64);
+        let b = BigUint::from(0x20u32);
+        let a_enc = FixedBigUintFHE::<2>::new(a.clone(), &client_key).unwrap();
+        let b_enc = FixedBigUintFHE::<2>::new(b.clone(), &client_key).unwrap();
+
+        // Values that do not fit are rejected
+        assert!(FixedBigUintFHE::<2>::new(modulus.clone(), &client_key).is_err());
+
+        let (sum, carry) = a_enc.overflowing_add(&b_enc);
+        assert_eq!(sum.to_biguint(&client_key), (&a + &b) % &modulus);
+        assert!(FheDecrypt::<bool>::decrypt(&carry, &client_key));
+
+        let (diff, borrow) = b_enc.overflowing_sub(&a_enc);
+        assert_eq!(diff.to_biguint(&client_key), (&b + &modulus - &a) % &modulus);
+        assert!(FheDecrypt::<bool>::decrypt(&borrow, &client_key));
+
+        let diff = a_enc.clone() - b_enc.clone();
+        assert_eq!(diff.to_biguint(&client_key), &a - &b);
+
+        // The width stays fixed through the conversions
+        let wide = BigUintFHE::from(a_enc.clone()) + BigUintFHE::from(b_enc);
+        assert_eq!(wide.digits().len(), 3);
+        let wrapped = FixedBigUintFHE::<2>::wrapping_from(&wide);
+        assert_eq!(wrapped.to_biguint(&client_key), (&a + &b) % &modulus);
+    }
+
+    #[test]
+    fn test_fixed_mul() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let modulus = BigUint::from(1u32) << 64u32;
+        let a = BigUint::from(0x12345678_9ABCDEF0u64);
+        let b = BigUint::from(0xFEDCBA98_76543210u64);
+        let a_enc = FixedBigUintFHE::<2>::new(a.clone(), &client_key).unwrap();
+        let b_enc = FixedBigUintFHE::<2>::new(b.clone(), &client_key).unwrap();
+
+        let product = a_enc.clone() * b_enc;
+        assert_eq!(product.to_biguint(&client_key), (&a * &b) % &modulus);
+
+        let product = a_enc.wrapping_mul_clear(&b);
+        assert_eq!(product.to_biguint(&client_key), (&a * &b) % &modulus);
+
+        // Single-digit overflow detection
+        let x = FixedBigUintFHE::<1>::new(BigUint::from(0x10000u32), &client_key).unwrap();
+        let (square, overflow) = x.overflowing_mul(&x);
+        assert_eq!(square.to_biguint(&client_key), BigUint::from(0u32));
+        assert!(FheDecrypt::<bool>::decrypt(&overflow, &client_key));
+    }
+}
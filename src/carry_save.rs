@@ -0,0 +1,295 @@
+use tfhe::prelude::*;
+use tfhe::{FheUint32, FheUint64, FheUint128};
+use num_bigint::BigUint;
+use crate::biguint::BigUintFHE;
+
+/// Largest public bound a column may reach. The headroom below 2^128 absorbs the incoming carry
+/// during normalisation, which is always below 2^97.
+const COLUMN_BOUND: u128 = u128::MAX - (1 << 97);
+
+/// Upper bound of a product of two u32 digits, (2^32 - 1)^2
+const PRODUCT_BOUND: u128 = (u32::MAX as u128) * (u32::MAX as u128);
+
+/// A carry-save accumulator over a fixed number of 32-bit digit positions.
+///
+/// Every position holds an encrypted `FheUint128` column wide enough to absorb whole 64-bit
+/// digit products without extracting a carry. The public upper bound of each column is tracked,
+/// and a column only has its carry moved up when the next term could overflow it. All remaining
+/// carries are resolved in a single pass by `normalize`, so an `n x m` digit product needs
+/// `n + m` carry extractions instead of two per digit product. Terms landing above the last
+/// position are dropped, so the result is the sum modulo 2^(32 * len).
+#[derive(Clone)]
+pub struct CarrySaveAccumulator {
+    // `None` marks a column that is still known to be zero
+    columns: Vec<Option<FheUint128>>,
+    // Public upper bound of every column
+    bounds: Vec<u128>,
+}
+
+impl CarrySaveAccumulator {
+    /// Creates an accumulator of `len` zero digit positions
+    pub fn new(len: usize) -> Self {
+        Self {
+            columns: vec![None; len],
+            bounds: vec![0; len],
+        }
+    }
+
+    /// Number of digit positions
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Returns true if the accumulator has no digit positions
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Adds an encrypted value
+    pub fn add(&mut self, value: &BigUintFHE) {
+        self.add_shifted(value, 0);
+    }
+
+    /// Adds an encrypted value multiplied by 2^(32 * offset)
+    pub fn add_shifted(&mut self, value: &BigUintFHE, offset: usize) {
+        for (i, digit) in value.digits().iter().enumerate() {
+            if offset + i >= self.len() {
+                break;
+            }
+            self.add_term(offset + i, FheUint128::cast_from(digit.clone()), u32::MAX as u128);
+        }
+    }
+
+    /// Adds a plaintext value. Its digits are added as scalars, so nothing is encrypted.
+    pub fn add_clear(&mut self, value: &BigUint) {
+        for (i, digit) in value.to_u32_digits().into_iter().enumerate() {
+            if i >= self.len() {
+                break;
+            }
+            if digit == 0 {
+                continue;
+            }
+            self.make_room(i, digit as u128);
+            self.columns[i] = Some(match self.columns[i].take() {
+                Some(column) => column + digit as u128,
+                None => FheUint128::encrypt_trivial(digit as u128),
+            });
+            self.bounds[i] += digit as u128;
+        }
+    }
+
+    /// Adds the product of two encrypted values
+    pub fn add_product(&mut self, a: &BigUintFHE, b: &BigUintFHE) {
+        self.add_product_digits(a.digits(), b.digits());
+    }
+
+    /// Adds the square of an encrypted value
+    pub fn add_square(&mut self, a: &BigUintFHE) {
+        self.add_square_digits(a.digits());
+    }
+
+    /// Adds the product of an encrypted value and a plaintext value
+    pub fn add_product_clear(&mut self, a: &BigUintFHE, b: &BigUint) {
+        self.add_product_clear_digits(a.digits(), &b.to_u32_digits());
+    }
+
+    /// Resolves all carries and returns the sum with at most `len` digits.
+    ///
+    /// The column bounds give a public bound on the sum, so top digits that no term can reach
+    /// are dropped.
+    pub fn normalize(self) -> BigUintFHE {
+        let bound = self.bounds.iter()
+            .rev()
+            .fold(BigUint::from(0u32), |acc, &bound| (acc << 32) + bound);
+        BigUintFHE::from_encrypted_digits(self.normalize_digits()).with_bound(&bound)
+    }
+
+    /// Adds every digit product `a_i * b_j` at position `i + j`. The products are computed on
+    /// `FheUint64`, which holds them exactly, and then only widened into the columns.
+    pub(crate) fn add_product_digits(&mut self, a: &[FheUint32], b: &[FheUint32]) {
+        for (i, a_digit) in a.iter().enumerate().take(self.len()) {
+            let a64 = FheUint64::cast_from(a_digit.clone());
+            for (j, b_digit) in b.iter().enumerate() {
+                if i + j >= self.len() {
+                    break;
+                }
+                let product = &a64 * FheUint64::cast_from(b_digit.clone());
+                self.add_term(i + j, FheUint128::cast_from(product), PRODUCT_BOUND);
+            }
+        }
+    }
+
+    /// Adds `a^2` digit product by digit product. Each cross product `a_i * a_j` with `i < j`
+    /// is computed once and doubled in its column, where `2 * (2^32 - 1)^2` still fits easily.
+    pub(crate) fn add_square_digits(&mut self, a: &[FheUint32]) {
+        let wide: Vec<FheUint64> = a.iter()
+            .take(self.len())
+            .map(|digit| FheUint64::cast_from(digit.clone()))
+            .collect();
+
+        for (i, a_i) in wide.iter().enumerate() {
+            if 2 * i < self.len() {
+                self.add_term(2 * i, FheUint128::cast_from(a_i * a_i), PRODUCT_BOUND);
+            }
+            for (j, a_j) in wide.iter().enumerate().skip(i + 1) {
+                if i + j >= self.len() {
+                    break;
+                }
+                let product = FheUint128::cast_from(a_i * a_j);
+                self.add_term(i + j, &product + &product, 2 * PRODUCT_BOUND);
+            }
+        }
+    }
+
+    /// Like `add_product_digits` with clear digits `b`. Zero clear digits are skipped entirely.
+    pub(crate) fn add_product_clear_digits(&mut self, a: &[FheUint32], b: &[u32]) {
+        for (i, a_digit) in a.iter().enumerate().take(self.len()) {
+            let a64 = FheUint64::cast_from(a_digit.clone());
+            for (j, &b_digit) in b.iter().enumerate() {
+                if i + j >= self.len() {
+                    break;
+                }
+                if b_digit == 0 {
+                    continue;
+                }
+                let product = &a64 * b_digit as u64;
+                self.add_term(i + j, FheUint128::cast_from(product), (u32::MAX as u128) * b_digit as u128);
+            }
+        }
+    }
+
+    /// Resolves all carries and returns exactly `len` digits, least significant first
+    pub(crate) fn normalize_digits(self) -> Vec<FheUint32> {
+        let mut digits = Vec::with_capacity(self.len());
+        let mut carry: Option<(FheUint128, u128)> = None;
+
+        for (column, bound) in self.columns.into_iter().zip(self.bounds) {
+            let (total, total_bound) = match (column, carry.take()) {
+                (Some(column), Some((c, c_bound))) => (column + c, bound + c_bound),
+                (Some(column), None) => (column, bound),
+                (None, Some(c)) => c,
+                (None, None) => {
+                    digits.push(FheUint32::encrypt_trivial(0u32));
+                    continue;
+                },
+            };
+
+            if total_bound <= u32::MAX as u128 {
+                // Provably no carry, the cast is exact
+                digits.push(FheUint32::cast_from(total));
+            } else {
+                digits.push(FheUint32::cast_from(&total & 0xFFFFFFFFu128));
+                carry = Some((total >> 32u128, total_bound >> 32));
+            }
+        }
+
+        digits
+    }
+
+    /// Adds an encrypted term with public upper bound `bound` to column `idx`
+    fn add_term(&mut self, idx: usize, term: FheUint128, bound: u128) {
+        if idx >= self.len() {
+            return;
+        }
+        self.make_room(idx, bound);
+        self.columns[idx] = Some(match self.columns[idx].take() {
+            Some(column) => column + term,
+            None => term,
+        });
+        self.bounds[idx] += bound;
+    }
+
+    /// Moves the carry of column `idx` up one position if adding `bound` could overflow it
+    fn make_room(&mut self, idx: usize, bound: u128) {
+        if self.bounds[idx] + bound <= COLUMN_BOUND {
+            return;
+        }
+        if let Some(column) = self.columns[idx].take() {
+            let carry_bound = self.bounds[idx] >> 32;
+            self.columns[idx] = Some(&column & 0xFFFFFFFFu128);
+            self.bounds[idx] = u32::MAX as u128;
+            self.add_term(idx + 1, column >> 32u128, carry_bound);
+        }
+    }
+}
+
+/// A clear or encrypted value that can be fed into a `CarrySaveAccumulator`.
+///
+/// Implemented for `BigUintFHE` and `BigUint`, so fused operations such as `BigUintFHE::mul_add`
+/// accept either kind of operand in every slot.
+pub trait AccumulatorOperand {
+    /// Public upper bound of the value
+    fn bound(&self) -> BigUint;
+
+    /// Adds the value to `acc`
+    fn add_to(&self, acc: &mut CarrySaveAccumulator);
+
+    /// Adds `lhs * self` to `acc`
+    fn add_product_to(&self, lhs: &BigUintFHE, acc: &mut CarrySaveAccumulator);
+}
+
+impl AccumulatorOperand for BigUintFHE {
+    fn bound(&self) -> BigUint {
+        BigUintFHE::bound(self).clone()
+    }
+
+    fn add_to(&self, acc: &mut CarrySaveAccumulator) {
+        acc.add(self);
+    }
+
+    fn add_product_to(&self, lhs: &BigUintFHE, acc: &mut CarrySaveAccumulator) {
+        acc.add_product(lhs, self);
+    }
+}
+
+impl AccumulatorOperand for BigUint {
+    fn bound(&self) -> BigUint {
+        self.clone()
+    }
+
+    fn add_to(&self, acc: &mut CarrySaveAccumulator) {
+        acc.add_clear(self);
+    }
+
+    fn add_product_to(&self, lhs: &BigUintFHE, acc: &mut CarrySaveAccumulator) {
+        acc.add_product_clear(lhs, self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tfhe::ConfigBuilder;
+
+    #[test]
+    fn test_carry_save_accumulator() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let a = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFF", 16).unwrap();
+        let b = BigUint::parse_bytes(b"FFFFFFFF00000001", 16).unwrap();
+        let k = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFF", 16).unwrap();
+        let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+        let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+
+        // k + a * b + a * b + (a << 32) + a, with carries resolved once
+        let mut acc = CarrySaveAccumulator::new(5);
+        acc.add_clear(&k);
+        acc.add_product(&a_enc, &b_enc);
+        acc.add_product_clear(&a_enc, &b);
+        acc.add_shifted(&a_enc, 1);
+        acc.add(&a_enc);
+        let sum = acc.normalize();
+
+        let expected = &k + &a * &b * 2u32 + (&a << 32) + &a;
+        assert_eq!(sum.digits().len(), 5);
+        assert_eq!(sum.to_biguint(&client_key), expected);
+
+        // Terms above the last position are dropped
+        let mut acc = CarrySaveAccumulator::new(2);
+        acc.add_product(&a_enc, &b_enc);
+        let modulus = BigUint::from(1u32) << 64;
+        assert_eq!(acc.normalize().to_biguint(&client_key), (&a * &b) % &modulus);
+    }
+}
@@ -54,7 +54,7 @@ impl FieldElement {
 
         // Make positive
         while t < BigInt::from(0) {
-            t = t + BigInt::from(self.order.clone());
+            t += BigInt::from(self.order.clone());
         }
 
         // Convert back to BigUint and reduce mod n
@@ -153,7 +153,7 @@ impl Sub for FieldElement {
         assert_eq!(self.order, other.order, "Cannot subtract elements from different fields");
         let mut result = self.value;
         if result < other.value {
-            result = result + &self.order;
+            result += &self.order;
         }
         Self {
             value: (result - other.value) % &self.order,
@@ -169,7 +169,7 @@ impl<'a> Sub<&'a FieldElement> for FieldElement {
         assert_eq!(self.order, other.order, "Cannot subtract elements from different fields");
         let mut result = self.value;
         if result < other.value {
-            result = result + &self.order;
+            result += &self.order;
         }
         Self {
             value: (result - &other.value) % &self.order,
@@ -185,7 +185,7 @@ impl<'a> Sub<&'a FieldElement> for &'a FieldElement {
         assert_eq!(self.order, other.order, "Cannot subtract elements from different fields");
         let mut result = self.value.clone();
         if result < other.value {
-            result = result + &self.order;
+            result += &self.order;
         }
         FieldElement {
             value: (result - &other.value) % &self.order,
@@ -0,0 +1,136 @@
+use num_bigint::BigUint;
+use crate::backend::FheBackend;
+use crate::biguint::BigUintFHE;
+
+/// Largest supported window, in bits. Each window stores `2^w - 1` encrypted entries.
+pub const MAX_WINDOW_BITS: usize = 8;
+
+/// A table of encrypted multiples of a private key `d`, for multiplying it by public scalars.
+///
+/// For a window width `w`, window `i` holds `d * j * 2^(w * i) mod n` for every `j` in
+/// `1..2^w`. Multiplying by a clear `e` then only selects, by the cleartext window digits of `e`,
+/// one entry per window and adds them up: no encrypted multiplication is needed. Building the
+/// table is done once at setup, also with additions only. Entries can be in any `FheBackend`.
+#[derive(Clone)]
+pub struct EncryptedKeyTable<B = BigUintFHE> {
+    window_bits: usize,
+    modulus: BigUint,
+    // entries[i][j - 1] = d * j * 2^(w * i) mod n
+    entries: Vec<Vec<B>>,
+}
+
+impl<B: FheBackend> EncryptedKeyTable<B> {
+    /// Builds the table for an encrypted key modulo `modulus` with `window_bits`-bit windows.
+    ///
+    /// Every entry is the previous one plus the window base, reduced modulo `n`, and `2^w` times
+    /// a window base is the base of the next window. Setup therefore costs about
+    /// `2^w * bits(n) / w` modular additions. Needs only the server key.
+    pub fn new(key: &B, modulus: &BigUint, window_bits: usize) -> Result<Self, tfhe::Error> {
+        if window_bits == 0 || window_bits > MAX_WINDOW_BITS {
+            return Err(format!("Window of {} bits is outside 1..={}", window_bits, MAX_WINDOW_BITS).into());
+        }
+        if *modulus < BigUint::from(2u32) {
+            return Err("Modulus must be at least 2".into());
+        }
+
+        let windows = Self::window_count(modulus, window_bits);
+        let mut entries = Vec::with_capacity(windows);
+        let mut base = key.reduce_mod(modulus);
+
+        for _ in 0..windows {
+            let mut window = Vec::with_capacity((1 << window_bits) - 1);
+            window.push(base.clone());
+            for _ in 2..(1 << window_bits) {
+                let next = window.last().unwrap().add(&base).reduce_mod(modulus);
+                window.push(next);
+            }
+            base = window.last().unwrap().add(&base).reduce_mod(modulus);
+            entries.push(window);
+        }
+
+        Ok(Self { window_bits, modulus: modulus.clone(), entries })
+    }
+
+    /// Width of each window in bits
+    pub fn window_bits(&self) -> usize {
+        self.window_bits
+    }
+
+    /// The modulus every entry is reduced by
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Number of encrypted entries stored
+    pub fn len(&self) -> usize {
+        self.entries.iter().map(Vec::len).sum()
+    }
+
+    /// Returns true if the table stores no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Computes `e * d mod n` for a public `e`
+    pub fn mul_clear(&self, e: &BigUint) -> B {
+        self.mul_add_clear(e, &BigUint::from(0u32))
+    }
+
+    /// Computes `(k + e * d) mod n` for public `e` and `k`.
+    ///
+    /// The selected entries and `k` are added with `FheBackend::sum_add_clear`, which on
+    /// `BigUintFHE` costs one carry resolution, followed by one reduction modulo `n`.
+    pub fn mul_add_clear(&self, e: &BigUint, k: &BigUint) -> B {
+        let e = e % &self.modulus;
+        let k = k % &self.modulus;
+        let mask = (1u32 << self.window_bits) - 1;
+
+        let mut selected = Vec::with_capacity(self.entries.len());
+        for (i, window) in self.entries.iter().enumerate() {
+            let j = ((&e >> (self.window_bits * i)) & BigUint::from(mask)).to_u32_digits();
+            if let Some(&j) = j.first() {
+                selected.push(&window[j as usize - 1]);
+            }
+        }
+
+        B::sum_add_clear(&selected, &k).reduce_mod(&self.modulus)
+    }
+
+    /// Number of windows covering every value below `modulus`
+    fn window_count(modulus: &BigUint, window_bits: usize) -> usize {
+        let bits = (modulus - 1u32).bits() as usize;
+        std::cmp::max(1, bits.div_ceil(window_bits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tfhe::ConfigBuilder;
+
+    #[test]
+    fn test_key_table_mul_add() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        // 2^32 - 5 is prime
+        let modulus = BigUint::parse_bytes(b"FFFFFFFB", 16).unwrap();
+        let d = BigUint::parse_bytes(b"FEDCBA98", 16).unwrap();
+        let d_enc = BigUintFHE::new(d.clone(), &client_key).unwrap();
+
+        assert!(EncryptedKeyTable::new(&d_enc, &modulus, 0).is_err());
+        let table = EncryptedKeyTable::new(&d_enc, &modulus, 2).unwrap();
+        assert_eq!(table.len(), 16 * 3);
+
+        let e = BigUint::parse_bytes(b"F0E1D2C3", 16).unwrap();
+        let k = BigUint::parse_bytes(b"12345678", 16).unwrap();
+        let s = table.mul_add_clear(&e, &k);
+        assert_eq!(s.digits().len(), 1);
+        assert_eq!(s.to_biguint(&client_key), (&k + &e * &d) % &modulus);
+
+        // A zero window digit selects nothing
+        let product = table.mul_clear(&BigUint::from(0x100u32));
+        assert_eq!(product.to_biguint(&client_key), (&d << 8) % &modulus);
+    }
+}
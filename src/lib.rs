@@ -3,4 +3,13 @@ pub mod scalar;
 pub mod secp256k1;
 pub mod schnorr;
 pub mod perf_test;
-pub mod biguint;
\ No newline at end of file
+pub mod biguint;
+pub mod biguint_bits;
+pub mod pseudo_mersenne;
+pub mod serialization;
+pub mod biguint_fixed;
+pub mod carry_save;
+pub mod key_table;
+pub mod limbs;
+pub mod backend;
+pub mod mock_backend;
@@ -0,0 +1,317 @@
+use std::ops::{Add, Mul};
+use tfhe::prelude::*;
+use tfhe::{ClientKey, FheUint8, FheUint16, FheUint32, FheUint64, FheUint128};
+use num_bigint::BigUint;
+use crate::biguint::BigUintFHE;
+
+/// An encrypted unsigned integer type usable as the limb of a `LimbBigUintFHE`.
+///
+/// `Wide` is twice as wide as the limb, so it holds a limb product plus a limb-sized addend and
+/// carry exactly. Layout conversions go through the bytes of each limb, which are moved with
+/// casts and shifts by clear amounts only, so no decryption is needed.
+pub trait FheLimb: Clone + Send + Sync {
+    /// Number of bits in a limb
+    const BITS: usize;
+
+    /// Encrypted type of twice the limb width
+    type Wide: Clone;
+
+    /// Encrypts the lowest `BITS` bits of `value`
+    fn encrypt_limb(value: u64, client_key: &ClientKey) -> Result<Self, tfhe::Error>;
+
+    /// Trivially encrypts the lowest `BITS` bits of a public `value`
+    fn trivial_limb(value: u64) -> Self;
+
+    /// Decrypts the limb
+    fn decrypt_limb(&self, client_key: &ClientKey) -> u64;
+
+    /// Casts the limb to the wide type
+    fn widen(&self) -> Self::Wide;
+
+    /// Adds two wide values
+    fn wide_add(a: Self::Wide, b: Self::Wide) -> Self::Wide;
+
+    /// Multiplies two wide values
+    fn wide_mul(a: &Self::Wide, b: &Self::Wide) -> Self::Wide;
+
+    /// Lower `BITS` bits of a wide value
+    fn lower_half(wide: &Self::Wide) -> Self;
+
+    /// Upper `BITS` bits of a wide value, still in the wide type
+    fn upper_half(wide: Self::Wide) -> Self::Wide;
+
+    /// Splits the limb into `BITS / 8` encrypted bytes, least significant first
+    fn split_bytes(&self) -> Vec<FheUint8>;
+
+    /// Joins up to `BITS / 8` encrypted bytes, least significant first, into a limb
+    fn join_bytes(bytes: &[FheUint8]) -> Self;
+}
+
+macro_rules! impl_fhe_limb {
+    ($limb:ty, $clear:ty, $wide:ty, $wide_clear:ty) => {
+        impl FheLimb for $limb {
+            const BITS: usize = <$clear>::BITS as usize;
+
+            type Wide = $wide;
+
+            fn encrypt_limb(value: u64, client_key: &ClientKey) -> Result<Self, tfhe::Error> {
+                <$limb>::try_encrypt(value as $clear, client_key)
+            }
+
+            fn trivial_limb(value: u64) -> Self {
+                <$limb>::encrypt_trivial(value as $clear)
+            }
+
+            fn decrypt_limb(&self, client_key: &ClientKey) -> u64 {
+                let value: $clear = self.decrypt(client_key);
+                value as u64
+            }
+
+            fn widen(&self) -> $wide {
+                <$wide>::cast_from(self.clone())
+            }
+
+            fn wide_add(a: $wide, b: $wide) -> $wide {
+                a + b
+            }
+
+            fn wide_mul(a: &$wide, b: &$wide) -> $wide {
+                a * b
+            }
+
+            fn lower_half(wide: &$wide) -> Self {
+                <$limb>::cast_from(wide & (<$clear>::MAX as $wide_clear))
+            }
+
+            fn upper_half(wide: $wide) -> $wide {
+                wide >> (<$clear>::BITS as $wide_clear)
+            }
+
+            fn split_bytes(&self) -> Vec<FheUint8> {
+                (0..<$clear>::BITS / 8)
+                    .map(|k| FheUint8::cast_from(self >> (8 * k) as $clear))
+                    .collect()
+            }
+
+            fn join_bytes(bytes: &[FheUint8]) -> Self {
+                bytes.iter()
+                    .enumerate()
+                    .map(|(k, byte)| <$limb>::cast_from(byte.clone()) << (8 * k) as $clear)
+                    .reduce(|acc, part| acc | part)
+                    .unwrap_or_else(|| <$limb>::encrypt_trivial(<$clear>::MIN))
+            }
+        }
+    };
+}
+
+impl_fhe_limb!(FheUint8, u8, FheUint16, u16);
+impl_fhe_limb!(FheUint16, u16, FheUint32, u32);
+impl_fhe_limb!(FheUint32, u32, FheUint64, u64);
+impl_fhe_limb!(FheUint64, u64, FheUint128, u128);
+
+/// An arbitrary-size unsigned integer encrypted limb by limb, with a configurable limb type.
+///
+/// `BigUintFHE` is the 32-bit layout with the full set of operations. This type provides the
+/// core arithmetic for every limb width, so the layouts can be benchmarked against each other,
+/// and `convert` regroups limbs into another layout homomorphically.
+#[derive(Clone)]
+pub struct LimbBigUintFHE<L: FheLimb> {
+    // Least significant limb first
+    limbs: Vec<L>,
+}
+
+/// 8-bit limbs with `FheUint16` intermediates
+pub type BigUintFHE8 = LimbBigUintFHE<FheUint8>;
+
+/// 16-bit limbs with `FheUint32` intermediates
+pub type BigUintFHE16 = LimbBigUintFHE<FheUint16>;
+
+/// 32-bit limbs with `FheUint64` intermediates, the layout of `BigUintFHE`
+pub type BigUintFHE32 = LimbBigUintFHE<FheUint32>;
+
+/// 64-bit limbs with `FheUint128` intermediates
+pub type BigUintFHE64 = LimbBigUintFHE<FheUint64>;
+
+impl<L: FheLimb> LimbBigUintFHE<L> {
+    /// Encrypts a BigUint value with the client key
+    pub fn new(value: BigUint, client_key: &ClientKey) -> Result<Self, tfhe::Error> {
+        let limbs = Self::clear_limbs(&value).into_iter()
+            .map(|d| L::encrypt_limb(d, client_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { limbs })
+    }
+
+    /// Creates a trivially encrypted value from a public constant
+    pub fn trivial(value: &BigUint) -> Self {
+        let limbs = Self::clear_limbs(value).into_iter()
+            .map(L::trivial_limb)
+            .collect();
+        Self { limbs }
+    }
+
+    /// Creates a value from encrypted limbs, least significant first
+    pub fn from_limbs(limbs: Vec<L>) -> Self {
+        Self { limbs }
+    }
+
+    /// Returns the encrypted limbs, least significant first
+    pub fn limbs(&self) -> &[L] {
+        &self.limbs
+    }
+
+    /// Decrypts the value to a BigUint
+    pub fn to_biguint(&self, client_key: &ClientKey) -> BigUint {
+        self.limbs.iter()
+            .rev()
+            .fold(BigUint::from(0u32), |acc, limb| (acc << L::BITS) + limb.decrypt_limb(client_key))
+    }
+
+    /// Regroups the limbs into another layout without decrypting.
+    ///
+    /// Each limb is split into encrypted bytes, which are then joined into the target limbs.
+    /// The result has as many limbs as needed to hold all the bits of the current ones.
+    pub fn convert<M: FheLimb>(&self) -> LimbBigUintFHE<M> {
+        let bytes: Vec<FheUint8> = self.limbs.iter()
+            .flat_map(|limb| limb.split_bytes())
+            .collect();
+        let limbs = bytes.chunks(M::BITS / 8)
+            .map(M::join_bytes)
+            .collect();
+        LimbBigUintFHE { limbs }
+    }
+
+    /// Splits a clear value into limbs of `L::BITS` bits
+    fn clear_limbs(value: &BigUint) -> Vec<u64> {
+        let mask = u64::MAX >> (64 - L::BITS);
+        let mut limbs: Vec<u64> = value.to_u64_digits().into_iter()
+            .flat_map(|d| (0..64 / L::BITS).map(move |k| (d >> (k * L::BITS)) & mask))
+            .collect();
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        limbs
+    }
+}
+
+impl From<BigUintFHE> for BigUintFHE32 {
+    fn from(value: BigUintFHE) -> Self {
+        Self::from_limbs(value.digits().to_vec())
+    }
+}
+
+impl From<BigUintFHE32> for BigUintFHE {
+    fn from(value: BigUintFHE32) -> Self {
+        BigUintFHE::from_encrypted_digits(value.limbs)
+    }
+}
+
+impl<L: FheLimb> Add for LimbBigUintFHE<L> {
+    type Output = Self;
+
+    /// Ripple-carry addition. The result has one limb more than the longer operand.
+    fn add(self, other: Self) -> Self {
+        let len = std::cmp::max(self.limbs.len(), other.limbs.len());
+        if len == 0 {
+            return Self { limbs: Vec::new() };
+        }
+
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry: Option<L::Wide> = None;
+        for i in 0..len {
+            let terms = [self.limbs.get(i).map(L::widen), other.limbs.get(i).map(L::widen), carry.take()];
+            // At least one operand has a limb at every position below len
+            let sum = terms.into_iter().flatten().reduce(L::wide_add).unwrap();
+            limbs.push(L::lower_half(&sum));
+            carry = Some(L::upper_half(sum));
+        }
+        if let Some(c) = carry {
+            limbs.push(L::lower_half(&c));
+        }
+
+        Self { limbs }
+    }
+}
+
+impl<L: FheLimb> Mul for LimbBigUintFHE<L> {
+    type Output = Self;
+
+    /// Schoolbook multiplication. The result has as many limbs as both operands together.
+    fn mul(self, other: Self) -> Self {
+        if self.limbs.is_empty() || other.limbs.is_empty() {
+            return Self { limbs: Vec::new() };
+        }
+
+        let out_len = self.limbs.len() + other.limbs.len();
+        // `None` marks a result limb that is still known to be zero
+        let mut result: Vec<Option<L>> = vec![None; out_len];
+        let wide_other: Vec<L::Wide> = other.limbs.iter().map(L::widen).collect();
+
+        for (i, a) in self.limbs.iter().enumerate() {
+            let a_wide = a.widen();
+            let mut carry: Option<L::Wide> = None;
+
+            for (j, b_wide) in wide_other.iter().enumerate() {
+                // r[i + j] + a * b + carry <= 2^(2 * BITS) - 1, so the sum fits in the wide type
+                let mut sum = L::wide_mul(&a_wide, b_wide);
+                if let Some(r) = result[i + j].take() {
+                    sum = L::wide_add(sum, r.widen());
+                }
+                if let Some(c) = carry.take() {
+                    sum = L::wide_add(sum, c);
+                }
+
+                result[i + j] = Some(L::lower_half(&sum));
+                carry = Some(L::upper_half(sum));
+            }
+
+            if let Some(c) = carry {
+                result[i + other.limbs.len()] = Some(L::lower_half(&c));
+            }
+        }
+
+        let limbs = result.into_iter()
+            .map(|d| d.unwrap_or_else(|| L::trivial_limb(0)))
+            .collect();
+        Self { limbs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tfhe::ConfigBuilder;
+
+    #[test]
+    fn test_limb_layouts() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let a = BigUint::parse_bytes(b"FFFFFFFFFFFF", 16).unwrap();
+        let b = BigUint::parse_bytes(b"FFFF0001", 16).unwrap();
+
+        let a8 = BigUintFHE8::new(a.clone(), &client_key).unwrap();
+        let b8 = BigUintFHE8::new(b.clone(), &client_key).unwrap();
+        assert_eq!(a8.limbs().len(), 6);
+        assert_eq!((a8.clone() + b8.clone()).to_biguint(&client_key), &a + &b);
+        assert_eq!((a8.clone() * b8).to_biguint(&client_key), &a * &b);
+
+        let a16 = BigUintFHE16::new(a.clone(), &client_key).unwrap();
+        let b16 = BigUintFHE16::new(b.clone(), &client_key).unwrap();
+        let b16_copy = b16.clone();
+        assert_eq!((a16 * b16).to_biguint(&client_key), &a * &b);
+
+        let a64 = BigUintFHE64::new(a.clone(), &client_key).unwrap();
+        let b64: BigUintFHE64 = b16_copy.convert();
+        assert_eq!(b64.limbs().len(), 1);
+        assert_eq!((a64 + b64).to_biguint(&client_key), &a + &b);
+
+        // Homomorphic conversions between layouts
+        let a32: BigUintFHE32 = a8.convert();
+        assert_eq!(a32.limbs().len(), 2);
+        assert_eq!(BigUintFHE::from(a32.clone()).to_biguint(&client_key), a);
+        let back: BigUintFHE8 = a32.convert();
+        assert_eq!(back.limbs().len(), 8);
+        assert_eq!(back.to_biguint(&client_key), a);
+    }
+}
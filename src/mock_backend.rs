@@ -0,0 +1,127 @@
+//! Plaintext mock of an FHE backend for tests and development.
+//!
+//! `MockUintFHE` implements `FheBackend` on a clear `BigUint`, so protocol code written against
+//! the trait runs end to end in milliseconds instead of hours. Nothing is hidden: use it only
+//! to check protocol logic. Every operation is counted per thread, like tfhe keeps its server
+//! key per thread, so concurrent tests do not see each other's counts.
+
+use std::cell::RefCell;
+use num_bigint::BigUint;
+use crate::backend::FheBackend;
+
+/// Number of times each operation ran on the current thread
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    pub encrypt: usize,
+    pub decrypt: usize,
+    pub trivial: usize,
+    pub add: usize,
+    pub add_clear: usize,
+    pub mul: usize,
+    pub mul_clear: usize,
+    pub reduce_mod: usize,
+}
+
+thread_local! {
+    static OP_COUNTS: RefCell<OpCounts> = RefCell::new(OpCounts::default());
+}
+
+/// Stand-in for a client key. Mock values can be "encrypted" and "decrypted" with it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockClientKey;
+
+/// A mock ciphertext that holds its value in the clear
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockUintFHE {
+    value: BigUint,
+}
+
+impl MockUintFHE {
+    /// Returns the operation counts of the current thread
+    pub fn op_counts() -> OpCounts {
+        OP_COUNTS.with(|counts| *counts.borrow())
+    }
+
+    /// Resets the operation counts of the current thread
+    pub fn reset_op_counts() {
+        OP_COUNTS.with(|counts| *counts.borrow_mut() = OpCounts::default());
+    }
+
+    /// Returns the value without going through `decrypt`, e.g. for debugging
+    pub fn value(&self) -> &BigUint {
+        &self.value
+    }
+
+    /// Records one run of an operation
+    fn count(op: impl FnOnce(&mut OpCounts)) {
+        OP_COUNTS.with(|counts| op(&mut counts.borrow_mut()));
+    }
+}
+
+impl FheBackend for MockUintFHE {
+    type ClientKey = MockClientKey;
+
+    fn encrypt(value: &BigUint, _client_key: &MockClientKey) -> Result<Self, tfhe::Error> {
+        Self::count(|c| c.encrypt += 1);
+        Ok(Self { value: value.clone() })
+    }
+
+    fn decrypt(&self, _client_key: &MockClientKey) -> BigUint {
+        Self::count(|c| c.decrypt += 1);
+        self.value.clone()
+    }
+
+    fn trivial(value: &BigUint) -> Self {
+        Self::count(|c| c.trivial += 1);
+        Self { value: value.clone() }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self::count(|c| c.add += 1);
+        Self { value: &self.value + &other.value }
+    }
+
+    fn add_clear(&self, other: &BigUint) -> Self {
+        Self::count(|c| c.add_clear += 1);
+        Self { value: &self.value + other }
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Self::count(|c| c.mul += 1);
+        Self { value: &self.value * &other.value }
+    }
+
+    fn mul_clear(&self, other: &BigUint) -> Self {
+        Self::count(|c| c.mul_clear += 1);
+        Self { value: &self.value * other }
+    }
+
+    fn reduce_mod(&self, modulus: &BigUint) -> Self {
+        assert!(*modulus != BigUint::from(0u32), "Cannot reduce modulo zero");
+        Self::count(|c| c.reduce_mod += 1);
+        Self { value: &self.value % modulus }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_counts_operations() {
+        MockUintFHE::reset_op_counts();
+        let key = MockClientKey;
+
+        let a = MockUintFHE::encrypt(&BigUint::from(7u32), &key).unwrap();
+        let b = MockUintFHE::trivial(&BigUint::from(5u32));
+        let result = a.mul(&b).add_clear(&BigUint::from(4u32)).reduce_mod(&BigUint::from(10u32));
+        assert_eq!(result.decrypt(&key), BigUint::from(9u32));
+
+        let counts = MockUintFHE::op_counts();
+        assert_eq!(counts, OpCounts { encrypt: 1, decrypt: 1, trivial: 1, mul: 1, add_clear: 1, reduce_mod: 1, ..Default::default() });
+
+        // Other threads keep their own counts
+        let other = std::thread::spawn(MockUintFHE::op_counts).join().unwrap();
+        assert_eq!(other, OpCounts::default());
+    }
+}
@@ -1,8 +1,10 @@
-use sha2::{Sha256, Digest};
-use rand::Rng;
 use std::time::Instant;
 use tfhe::prelude::*;
-use tfhe::{generate_keys, set_server_key, ConfigBuilder, FheUint32, FheUint8, ClientKey, FheBool};
+use tfhe::{generate_keys, set_server_key, ClientKey, ConfigBuilder, FheUint32, FheUint8};
+use num_bigint::BigUint;
+use crate::limbs::{FheLimb, LimbBigUintFHE};
+use crate::biguint::BigUintFHE;
+use crate::scalar::{get_curve_order, get_field_size};
 
 pub fn perf_test() {
     // Basic configuration to use homomorphic integers
@@ -25,11 +27,11 @@ pub fn perf_test() {
 
     // Perform homomorphic operations
     let start_add = Instant::now();
-    let encrypted_res_add = &encrypted_a + &encrypted_b; // 1344 * 5
+    let _encrypted_res_add = &encrypted_a + &encrypted_b; // 1344 * 5
     let end_add = Instant::now();
     println!("Time taken for add: {:?}", end_add.duration_since(start_add));
     let start_mul = Instant::now();
-    let encrypted_res_mul = &encrypted_a * &encrypted_b; // 1344 * 5
+    let _encrypted_res_mul = &encrypted_a * &encrypted_b; // 1344 * 5
     let end_mul = Instant::now();
     println!("Time taken for mul: {:?}", end_mul.duration_since(start_mul));
     let start_shift = Instant::now();
@@ -91,12 +93,85 @@ pub fn perf_test() {
     // Time taken for float div: 78ns
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Times a 256-bit by 256-bit multiplication with every limb layout and on `BigUintFHE`, and the
+/// homomorphic conversion of the operands into the 32-bit layout of `BigUintFHE`
+pub fn limb_width_perf_test() {
+    let config = ConfigBuilder::default().build();
+    let (client_key, server_keys) = generate_keys(config);
+    set_server_key(server_keys.clone());
+
+    let a: BigUint = (BigUint::from(1u32) << 256) - BigUint::from(1u32);
+    let b: BigUint = (BigUint::from(1u32) << 255) + BigUint::from(12345u32);
+
+    time_limb_mul::<FheUint8>("8-bit", &a, &b, &client_key);
+    time_limb_mul::<tfhe::FheUint16>("16-bit", &a, &b, &client_key);
+    time_limb_mul::<FheUint32>("32-bit", &a, &b, &client_key);
+    time_limb_mul::<tfhe::FheUint64>("64-bit", &a, &b, &client_key);
+
+    let a_enc = BigUintFHE::new(a.clone(), &client_key).unwrap();
+    let b_enc = BigUintFHE::new(b.clone(), &client_key).unwrap();
+
+    let start_mul = Instant::now();
+    let product = a_enc.clone() * b_enc.clone();
+    println!("Time taken for BigUintFHE mul: {:?}", start_mul.elapsed());
+    assert_eq!(product.to_biguint(&client_key), &a * &b);
+
+    let start_par_mul = Instant::now();
+    let product = a_enc.par_mul(&b_enc, &server_keys);
+    println!("Time taken for BigUintFHE par_mul: {:?}", start_par_mul.elapsed());
+    assert_eq!(product.to_biguint(&client_key), &a * &b);
+
+    time_limb_convert::<FheUint8>("8-bit", &a, &client_key);
+    time_limb_convert::<tfhe::FheUint16>("16-bit", &a, &client_key);
+    time_limb_convert::<tfhe::FheUint64>("64-bit", &a, &client_key);
+}
+
+/// Times the reduction of a 512-bit product modulo the secp256k1 moduli, with generic Barrett
+/// reduction and with the pseudo-Mersenne folds
+pub fn pseudo_mersenne_perf_test() {
+    let config = ConfigBuilder::default().build();
+    let (client_key, server_keys) = generate_keys(config);
+    set_server_key(server_keys);
+
+    let p = get_field_size();
+    let n = get_curve_order();
+    let x = (&n - BigUint::from(1u32)) * (&p - BigUint::from(1u32));
+    let x_enc = BigUintFHE::new(x.clone(), &client_key).unwrap();
+
+    let start_barrett = Instant::now();
+    let barrett = x_enc.reduce_mod(&p);
+    println!("Time taken for Barrett reduction mod p: {:?}", start_barrett.elapsed());
+    let start_folds = Instant::now();
+    let folded = x_enc.reduce_field_size();
+    println!("Time taken for pseudo-Mersenne reduction mod p: {:?}", start_folds.elapsed());
+    assert_eq!(barrett.to_biguint(&client_key), folded.to_biguint(&client_key));
+
+    let start_barrett = Instant::now();
+    let barrett = x_enc.reduce_mod(&n);
+    println!("Time taken for Barrett reduction mod n: {:?}", start_barrett.elapsed());
+    let start_folds = Instant::now();
+    let folded = x_enc.reduce_curve_order();
+    println!("Time taken for pseudo-Mersenne reduction mod n: {:?}", start_folds.elapsed());
+    assert_eq!(barrett.to_biguint(&client_key), folded.to_biguint(&client_key));
+}
 
-    #[test]
-    fn test_perf_test() {
-        perf_test();
-    }
-}
\ No newline at end of file
+/// Multiplies `a` and `b` encrypted with `L` limbs and prints the time taken
+fn time_limb_mul<L: FheLimb>(label: &str, a: &BigUint, b: &BigUint, client_key: &ClientKey) {
+    let a_enc = LimbBigUintFHE::<L>::new(a.clone(), client_key).unwrap();
+    let b_enc = LimbBigUintFHE::<L>::new(b.clone(), client_key).unwrap();
+
+    let start_mul = Instant::now();
+    let product = a_enc * b_enc;
+    println!("Time taken for mul with {} limbs: {:?}", label, start_mul.elapsed());
+    assert_eq!(product.to_biguint(client_key), a * b);
+}
+
+/// Encrypts `a` with `L` limbs, converts it to a `BigUintFHE` and prints the time taken
+fn time_limb_convert<L: FheLimb>(label: &str, a: &BigUint, client_key: &ClientKey) {
+    let a_enc = LimbBigUintFHE::<L>::new(a.clone(), client_key).unwrap();
+
+    let start_convert = Instant::now();
+    let converted = BigUintFHE::from(a_enc.convert::<FheUint32>());
+    println!("Time taken for {} -> 32-bit limb conversion: {:?}", label, start_convert.elapsed());
+    assert_eq!(converted.to_biguint(client_key), *a);
+}
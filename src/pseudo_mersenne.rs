@@ -0,0 +1,87 @@
+//! Reduction modulo pseudo-Mersenne numbers `m = 2^k - c` with a small `c`.
+//!
+//! Since `2^k ≡ c (mod m)`, a value `hi * 2^k + lo` is congruent to `hi * c + lo`. Each fold is a
+//! single `mul_add` by the clear constant `c`, so it needs far fewer digit products than a
+//! Barrett reduction. Both secp256k1 moduli have this form:
+//!
+//! - `p = 2^256 - 2^32 - 977`, whose `c` has two digits
+//! - `n = 2^256 - c` with a 129-bit `c`
+
+use num_bigint::BigUint;
+use crate::biguint::BigUintFHE;
+use crate::scalar::{get_curve_order, get_field_size};
+
+impl BigUintFHE {
+    /// Reduces the encrypted value modulo the secp256k1 field size `p`
+    pub fn reduce_field_size(&self) -> Self {
+        self.reduce_pseudo_mersenne(&get_field_size())
+    }
+
+    /// Reduces the encrypted value modulo the secp256k1 curve order `n`
+    pub fn reduce_curve_order(&self) -> Self {
+        self.reduce_pseudo_mersenne(&get_curve_order())
+    }
+
+    /// Reduces the encrypted value modulo `m = 2^k - c`, where `k` is a whole number of digits.
+    ///
+    /// The value is folded as `hi * c + lo` while its bound is at least `2m`, then a single
+    /// conditional subtraction in `reduce_mod` gives the canonical representative. With
+    /// `c < 2^(k - 2)` every fold shrinks the bound until that point is reached.
+    ///
+    /// Panics if the modulus is not of that form.
+    pub fn reduce_pseudo_mersenne(&self, modulus: &BigUint) -> Self {
+        let split = modulus.to_u32_digits().len();
+        let k = 32 * split;
+        let power = BigUint::from(1u32) << k;
+        assert!(*modulus > BigUint::from(0u32), "Cannot reduce modulo zero");
+        let c = &power - modulus;
+        assert!(c.bits() < k as u64 - 1, "Modulus is not of the form 2^k - c with c < 2^(k - 2)");
+
+        let twice = modulus * 2u32;
+        let mut value = self.clone();
+        while *value.bound() >= twice {
+            // Split at digit `split`: value = hi * 2^k + lo
+            let digits = value.digits();
+            let lo = BigUintFHE::from_encrypted_digits(digits[..split].to_vec())
+                .with_bound(value.bound());
+            let hi = BigUintFHE::from_encrypted_digits(digits[split..].to_vec())
+                .with_bound(&(value.bound() >> k));
+            value = hi.mul_add(&c, &lo);
+        }
+
+        value.reduce_mod(modulus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tfhe::ConfigBuilder;
+
+    #[test]
+    fn test_reduce_secp256k1_moduli() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let p = get_field_size();
+        let n = get_curve_order();
+        let max = (BigUint::from(1u32) << 512) - BigUint::from(1u32);
+        let product = (&n - BigUint::from(1u32)) * (&p - BigUint::from(5u32));
+
+        for x in [max, product] {
+            let x_enc = BigUintFHE::new(x.clone(), &client_key).unwrap();
+
+            let reduced = x_enc.reduce_field_size();
+            assert_eq!(reduced.bound(), &(&p - 1u32));
+            assert_eq!(reduced.to_biguint(&client_key), &x % &p);
+
+            let reduced = x_enc.reduce_curve_order();
+            assert_eq!(reduced.to_biguint(&client_key), &x % &n);
+        }
+
+        // Values already below the modulus are untouched
+        let small = BigUintFHE::from_u32(42u32, &client_key).unwrap();
+        assert_eq!(small.reduce_curve_order().to_biguint(&client_key), BigUint::from(42u32));
+    }
+}
@@ -9,12 +9,12 @@ const CURVE_ORDER: &str = "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD
 
 /// Returns the field size as a BigUint
 pub fn get_field_size() -> BigUint {
-    BigUint::parse_bytes(&FIELD_SIZE[2..].as_bytes(), 16).unwrap()
+    BigUint::parse_bytes(&FIELD_SIZE.as_bytes()[2..], 16).unwrap()
 }
 
 /// Returns the curve order as a BigUint
 pub fn get_curve_order() -> BigUint {
-    BigUint::parse_bytes(&CURVE_ORDER[2..].as_bytes(), 16).unwrap()
+    BigUint::parse_bytes(&CURVE_ORDER.as_bytes()[2..], 16).unwrap()
 }
 
 /// Creates a new field element in the base field (mod p).
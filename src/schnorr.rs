@@ -3,9 +3,10 @@ use crate::scalar::{get_curve_order, Scalar, get_field_size};
 use crate::field::FieldElement;
 use crate::secp256k1::Point;
 use num_bigint::BigUint;
-use tfhe::prelude::*;
-use tfhe::{generate_keys, set_server_key, ConfigBuilder, FheUint32, ClientKey};
+use tfhe::ClientKey;
 use crate::biguint::BigUintFHE;
+use crate::key_table::EncryptedKeyTable;
+use crate::backend::FheBackend;
 use std::time::Instant;
 
 // https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki
@@ -47,16 +48,39 @@ impl Signature {
     /// Serializes the signature to bytes according to BIP-340: R.x || s
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut sig = Vec::with_capacity(64);
-        sig.extend_from_slice(&bytes_from_int(&self.r_x.value()));
+        sig.extend_from_slice(&bytes_from_int(self.r_x.value()));
         sig.extend_from_slice(&bytes_from_int(self.s.value()));
         sig
     }
 }
 
+/// A Schnorr signature whose scalar `s` is still encrypted under the owner's FHE key
+#[derive(Clone)]
+pub struct EncryptedSignature<B = BigUintFHE> {
+    pub r_x: FieldElement,  // x-coordinate of R
+    pub s: B,              // encrypted scalar s, already reduced modulo n
+}
+
+impl<B: FheBackend> EncryptedSignature<B> {
+    /// Decrypts `s` with the owner's client key
+    pub fn decrypt(&self, client_key: &B::ClientKey) -> Signature {
+        Signature {
+            r_x: self.r_x.clone(),
+            s: Scalar::new(self.s.decrypt(client_key)),
+        }
+    }
+}
+
 /// The Schnorr signature scheme implementation following BIP-340
 pub struct Schnorr {
 }
 
+impl Default for Schnorr {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Schnorr {
     /// Creates a new Schnorr instance with the given private key.
     pub fn new() -> Self {
@@ -75,10 +99,10 @@ impl Schnorr {
     pub fn sign(&self, message: &[u8], aux_rand: &[u8], privkey: &Scalar) -> Result<Signature, Box<dyn std::error::Error>> {
         println!("Starting `sign` operation");
         let start_total = Instant::now();
-        let pubkey = get_public_key_with_even_y(privkey);
+        let (d, pubkey) = key_pair_with_even_y(privkey);
         let curve_order = get_curve_order();
         // Generate deterministic nonce k0 according to BIP-340
-        let k0 = compute_nonce(privkey.value(), &pubkey, message, aux_rand);
+        let k0 = compute_nonce(&d, &pubkey, message, aux_rand);
         let generator = Point::get_generator();
         let r = generator.scalar_mul(&Scalar::new(k0.clone()));
 
@@ -93,7 +117,7 @@ impl Schnorr {
         let e = compute_challenge(&r, &pubkey, message);
 
         // Compute s = (k + e * privkey) % n
-        let s = (k + e * privkey.value()) % get_curve_order();
+        let s = (k + e * &d) % get_curve_order();
         println!("`sign` operation time: {:?}", start_total.elapsed());
 
         Ok(Signature {
@@ -114,7 +138,7 @@ impl Schnorr {
     pub fn sign_with_k0(&self, message: &[u8], k0: &BigUint, privkey: &Scalar) -> Result<Signature, Box<dyn std::error::Error>> {
         println!("Starting `sign` operation");
         let start_total = Instant::now();
-        let pubkey = get_public_key_with_even_y(privkey);
+        let (d, pubkey) = key_pair_with_even_y(privkey);
         let curve_order = get_curve_order();
         // Generate deterministic nonce k0 according to BIP-340
         let generator = Point::get_generator();
@@ -131,7 +155,7 @@ impl Schnorr {
         let e = compute_challenge(&r, &pubkey, message);
 
         // Compute s = (k + e * privkey) % n
-        let s = (k + e * privkey.value()) % get_curve_order();
+        let s = (k + e * &d) % get_curve_order();
         println!("`sign` operation time: {:?}", start_total.elapsed());
 
         Ok(Signature {
@@ -143,6 +167,9 @@ impl Schnorr {
 
     /// Signs a message using the Schnorr signature scheme according to BIP-340 with FHE.
     ///
+    /// The private key is encrypted as a `BigUintFHE`; `sign_fhe_with_backend` is the generic
+    /// entry point for the other `FheBackend` implementations.
+    ///
     /// # Arguments
     /// * `message` - The message to be signed as a byte slice
     /// * `aux_rand` - The auxiliary random data as a byte slice
@@ -152,19 +179,33 @@ impl Schnorr {
     /// # Returns
     /// * `Result<Signature, tfhe::Error>` - The Schnorr signature if successful, or a TFHE error if encryption operations fail
     pub fn sign_fhe(&self, message: &[u8], aux_rand: &[u8], privkey: &Scalar, client_key: &ClientKey) -> Result<Signature, tfhe::Error> {
+        self.sign_fhe_with_backend::<BigUintFHE>(message, aux_rand, privkey, client_key)
+    }
+
+    /// Same as `sign_fhe`, with the private key encrypted in the arithmetic backend `B`.
+    ///
+    /// # Arguments
+    /// * `message` - The message to be signed as a byte slice
+    /// * `aux_rand` - The auxiliary random data as a byte slice
+    /// * `privkey` - The caller/owner's private key as a Scalar
+    /// * `client_key` - The backend's client key used for encryption/decryption
+    ///
+    /// # Returns
+    /// * `Result<Signature, tfhe::Error>` - The Schnorr signature if successful, or a TFHE error if encryption operations fail
+    pub fn sign_fhe_with_backend<B: FheBackend>(&self, message: &[u8], aux_rand: &[u8], privkey: &Scalar, client_key: &B::ClientKey) -> Result<Signature, tfhe::Error> {
         let start_total = Instant::now();
         println!("Starting `sign_fhe` operation");
 
         // Step 1: Get Public Key
         let start_public_key = Instant::now();
-        let pubkey = get_public_key_with_even_y(privkey);
+        let (d, pubkey) = key_pair_with_even_y(privkey);
         println!("`get_public_key` time: {:?}", start_public_key.elapsed());
 
         let curve_order = get_curve_order();
 
         // Step 2: Compute Nonce
         let start_nonce = Instant::now();
-        let k0 = compute_nonce(privkey.value(), &pubkey, message, aux_rand);
+        let k0 = compute_nonce(&d, &pubkey, message, aux_rand);
         println!("`compute_nonce` time: {:?}", start_nonce.elapsed());
 
         // Step 3: Compute R = kG
@@ -189,12 +230,10 @@ impl Schnorr {
 
         // Step 6: Compute s = (k + e * privkey) mod n using FHE operations
         let start_fhe_operations = Instant::now();
-        let privkey_fhe = BigUintFHE::new(privkey.value().clone(), client_key)?;
-        let e_fhe = BigUintFHE::new(e.clone(), client_key)?;
-        let k_fhe = BigUintFHE::new(k.clone(), client_key)?;
-        let s_fhe_without_mod = k_fhe + (e_fhe * privkey_fhe);
-        let s_without_mod = s_fhe_without_mod.to_biguint(client_key);
-        let s = s_without_mod % curve_order;
+        // e and k are public, only the private key needs to be encrypted
+        let privkey_fhe = B::encrypt(&d, client_key)?;
+        let s_fhe = privkey_fhe.mul_add_clear(&e, &k).reduce_mod(&curve_order);
+        let s = s_fhe.decrypt(client_key);
         println!("FHE operations (`k + e * privkey mod n`) time: {:?}", start_fhe_operations.elapsed());
 
         // Step 7: Construct the Signature
@@ -218,27 +257,105 @@ impl Schnorr {
     /// 3. considering our use case, alice store the main private key in encrypted form, then set access control rules, allow alice's working device to call the sign function, the working device also has a key pair which is visible to alice due to alice owns the working device. so we can use the working device's private key to generate k0. this avoid the expensive hash operation.
     /// 4. remind that we want to avoid the expensive hash operation, we need to use the working device's private key to in clear/unencrypted form, but we can not pass the private key as parameter to the sign function which happens onchain and publically visible to all. so we need the device to generate k0 with its private key offchain privately, and then pass k0 to the sign function.
     /// 5. addtional work for security reason
-    /// 5.1 if the working device is compromised, the private key can be leaked. so we need to ensure the sign function is still secure, which means cracker can not get alice's private key from s = (k + e * privkey) mod n, which means k0 should be unique for each signature.
-    /// 5.2 so we need to store the k0 values on chain, each sign operation need to check if the k0 is already used before.
-
+    ///    5.1 if the working device is compromised, the private key can be leaked. so we need to ensure the sign function is still secure, which means cracker can not get alice's private key from s = (k + e * privkey) mod n, which means k0 should be unique for each signature.
+    ///    5.2 so we need to store the k0 values on chain, each sign operation need to check if the k0 is already used before.
+    ///
     /// Signs a message using the Schnorr signature scheme according to BIP-340 with FHE and a pre-computed nonce k0.
     ///
     /// # Arguments
     /// * `message` - The message to be signed as a byte slice
     /// * `k0` - The pre-computed nonce value as a BigUint
     /// * `privkey` - The caller's private key as a Scalar
-    /// * `privkey_fhe` - The owner's private key encrypted as a BigUintFHE
-    /// * `client_key` - The FHE client key used for encryption/decryption
+    /// * `privkey_fhe` - The owner's private key encrypted in any arithmetic backend
+    /// * `client_key` - The backend's client key used for encryption/decryption
     ///
     /// # Returns
     /// * `Result<Signature, tfhe::Error>` - The Schnorr signature if successful, or a TFHE error if encryption operations fail
-    pub fn sign_fhe_with_k0(&self, message: &[u8], k0: &BigUint, privkey: &Scalar, privkey_fhe: &BigUintFHE, client_key: &ClientKey) -> Result<Signature, tfhe::Error> {
+    pub fn sign_fhe_with_k0<B: FheBackend>(&self, message: &[u8], k0: &BigUint, privkey: &Scalar, privkey_fhe: &B, client_key: &B::ClientKey) -> Result<Signature, tfhe::Error> {
         let start_total = Instant::now();
         println!("Starting `sign_fhe` operation");
 
+        // Steps 1-6 only need the server key
+        let encrypted_signature = self.sign_fhe_with_k0_encrypted(message, k0, privkey, privkey_fhe);
+
+        // Step 7: Decrypt s and construct the Signature
+        let start_construct_signature = Instant::now();
+        let signature = encrypted_signature.decrypt(client_key);
+        println!("`construct_signature` time: {:?}", start_construct_signature.elapsed());
+
+        println!("Total `sign_fhe` operation time: {:?}", start_total.elapsed());
+
+        Ok(signature)
+    }
+
+    /// Computes a BIP-340 signature with FHE and a pre-computed nonce k0, leaving `s` encrypted.
+    ///
+    /// This is the server-side half of `sign_fhe_with_k0`: it needs the server key to be set but
+    /// never the client key. The owner decrypts the result with `EncryptedSignature::decrypt`.
+    ///
+    /// # Arguments
+    /// * `message` - The message to be signed as a byte slice
+    /// * `k0` - The pre-computed nonce value as a BigUint
+    /// * `privkey` - The caller's private key as a Scalar
+    /// * `privkey_fhe` - The owner's private key encrypted in any arithmetic backend
+    ///
+    /// # Returns
+    /// * `EncryptedSignature<B>` - The signature with `s` encrypted under the owner's key
+    pub fn sign_fhe_with_k0_encrypted<B: FheBackend>(&self, message: &[u8], k0: &BigUint, privkey: &Scalar, privkey_fhe: &B) -> EncryptedSignature<B> {
+        // Steps 1-5 only involve public values
+        let (r, k, e) = self.prepare_with_k0(message, k0, privkey);
+        let curve_order = get_curve_order();
+
+        // Step 6: Compute s = (k + e * privkey) mod n using FHE operations
+        let start_fhe_operations = Instant::now();
+        // e and k are public, so they are applied as clear operands
+        let s_fhe = privkey_fhe.mul_add_clear(&e, &k).reduce_mod(&curve_order);
+        println!("FHE operations (`k + e * privkey mod n`) time: {:?}", start_fhe_operations.elapsed());
+
+        EncryptedSignature {
+            r_x: r.x,
+            s: s_fhe,
+        }
+    }
+
+    /// Computes a BIP-340 signature with a pre-computed nonce k0 and a table of encrypted key
+    /// multiples, leaving `s` encrypted.
+    ///
+    /// Same as `sign_fhe_with_k0_encrypted`, but `e * privkey` is a sum of table entries picked
+    /// by the cleartext bits of `e`, so no encrypted multiplication is performed. The table can be
+    /// built in any arithmetic backend.
+    ///
+    /// # Arguments
+    /// * `message` - The message to be signed as a byte slice
+    /// * `k0` - The pre-computed nonce value as a BigUint
+    /// * `privkey` - The caller's private key as a Scalar
+    /// * `key_table` - The owner's key table, built modulo the curve order
+    ///
+    /// # Returns
+    /// * `EncryptedSignature<B>` - The signature with `s` encrypted under the owner's key
+    pub fn sign_fhe_with_k0_table<B: FheBackend>(&self, message: &[u8], k0: &BigUint, privkey: &Scalar, key_table: &EncryptedKeyTable<B>) -> EncryptedSignature<B> {
+        assert_eq!(key_table.modulus(), &get_curve_order(), "Key table must be built modulo the curve order");
+
+        // Steps 1-5 only involve public values
+        let (r, k, e) = self.prepare_with_k0(message, k0, privkey);
+
+        // Step 6: Compute s = (k + e * privkey) mod n from the key table
+        let start_fhe_operations = Instant::now();
+        let s_fhe = key_table.mul_add_clear(&e, &k);
+        println!("FHE operations (`k + e * privkey mod n` from key table) time: {:?}", start_fhe_operations.elapsed());
+
+        EncryptedSignature {
+            r_x: r.x,
+            s: s_fhe,
+        }
+    }
+
+    /// Steps 1-5 of signing with a pre-computed nonce k0: returns R, the parity-adjusted k
+    /// and the challenge e
+    fn prepare_with_k0(&self, message: &[u8], k0: &BigUint, privkey: &Scalar) -> (Point, BigUint, BigUint) {
         // Step 1: Get Public Key
         let start_public_key = Instant::now();
-        let pubkey = get_public_key_with_even_y(privkey);
+        let (d, pubkey) = key_pair_with_even_y(privkey);
         println!("`get_public_key` time: {:?}", start_public_key.elapsed());
 
         let curve_order = get_curve_order();
@@ -264,29 +381,15 @@ impl Schnorr {
 
         // Step 5: Compute Challenge e = H(R || P || m)
         let start_challenge = Instant::now();
-        let e = compute_challenge(&r, &pubkey, message);
+        let mut e = compute_challenge(&r, &pubkey, message);
+        // The encrypted key is `privkey` as given. If BIP-340 signs with `n - privkey` instead,
+        // `k + e * (n - privkey) = k + (n - e) * privkey mod n`, so the public e is negated.
+        if d != *privkey.value() {
+            e = (&curve_order - e) % &curve_order;
+        }
         println!("`compute_challenge` time: {:?}", start_challenge.elapsed());
 
-        // Step 6: Compute s = (k + e * privkey) mod n using FHE operations
-        let start_fhe_operations = Instant::now();
-        let e_fhe = BigUintFHE::new(e.clone(), client_key)?;
-        let k_fhe = BigUintFHE::new(k.clone(), client_key)?;
-        let s_fhe_without_mod = k_fhe + (e_fhe * privkey_fhe.clone());
-        let s_without_mod = s_fhe_without_mod.to_biguint(client_key);
-        let s = s_without_mod % curve_order;
-        println!("FHE operations (`k + e * privkey mod n`) time: {:?}", start_fhe_operations.elapsed());
-
-        // Step 7: Construct the Signature
-        let start_construct_signature = Instant::now();
-        let signature = Signature {
-            r_x: r.x,
-            s: Scalar::new(s),
-        };
-        println!("`construct_signature` time: {:?}", start_construct_signature.elapsed());
-
-        println!("Total `sign_fhe` operation time: {:?}", start_total.elapsed());
-
-        Ok(signature)
+        (r, k, e)
     }
 
     /// Verifies a Schnorr signature according to BIP-340.
@@ -348,20 +451,22 @@ impl Schnorr {
 }
 
 
-/// Gets the public key with BIP-340 y-coordinate conventions which is even
-fn get_public_key_with_even_y(privkey: &Scalar) -> Point {
+/// Gets the private key BIP-340 signs with and its public key, whose y-coordinate is even.
+/// When `privkey * G` has an odd y-coordinate, both are negated.
+fn key_pair_with_even_y(privkey: &Scalar) -> (BigUint, Point) {
     let generator = Point::get_generator();
-    let pubkey = generator.scalar_mul(&privkey);
+    let pubkey = generator.scalar_mul(privkey);
 
     // Ensure the public key has an even y-coordinate as per BIP-340
     if pubkey.y.value() % BigUint::from(2u32) == BigUint::from(1u32) {
-        Point::new(
+        let negated = Point::new(
             pubkey.x.clone(),
             FieldElement::new(get_field_size() - pubkey.y.value(), get_field_size()),
             false
-        )
+        );
+        (get_curve_order() - privkey.value(), negated)
     } else {
-        pubkey
+        (privkey.value().clone(), pubkey)
     }
 }
 
@@ -370,8 +475,8 @@ fn get_public_key_with_even_y(privkey: &Scalar) -> Point {
 fn tagged_hash(tag: &[u8], msg: &[u8]) -> Vec<u8> {
     let tag_hash = Sha256::digest(tag);
     let mut hasher = Sha256::new();
-    hasher.update(&tag_hash);
-    hasher.update(&tag_hash);
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
     hasher.update(msg);
     hasher.finalize().to_vec()
 }
@@ -387,7 +492,7 @@ fn bytes_from_int(n: &BigUint) -> [u8; 32] {
 
 /// Converts a Point to a 32-byte array by taking its x-coordinate
 fn bytes_from_point(p: &Point) -> [u8; 32] {
-    bytes_from_int(&p.x.value())
+    bytes_from_int(p.x.value())
 }
 
 /// Computes the nonce according to BIP-340 specification
@@ -435,6 +540,9 @@ fn lift_x(x: &FieldElement) -> Point {
 mod tests {
     use super::*;
     use hex;
+    use tfhe::prelude::*;
+    use tfhe::{generate_keys, set_server_key, ConfigBuilder, FheUint32, FheUint512};
+    use crate::mock_backend::{MockClientKey, MockUintFHE};
 
     #[test]
     fn test_schnorr_fhe() {
@@ -451,7 +559,7 @@ mod tests {
         let seckey = Scalar::new(BigUint::from_bytes_be(&seckey_bytes));
         let schnorr = Schnorr::new();
         let sig = schnorr.sign(&message, &aux_rand, &seckey);
-        let pubkey = get_public_key_with_even_y(&seckey);
+        let (_, pubkey) = key_pair_with_even_y(&seckey);
 
         assert!(sig.is_ok());
         let sig = sig.unwrap();
@@ -478,10 +586,10 @@ mod tests {
 
         let privkey = Scalar::new(BigUint::from_bytes_be(&seckey_bytes));
         let privkey_fhe = BigUintFHE::new(privkey.value().clone(), &client_key).unwrap();
-        let pubkey = get_public_key_with_even_y(&privkey);
+        let (d, pubkey) = key_pair_with_even_y(&privkey);
 
         let schnorr = Schnorr::new();
-        let k0 = compute_nonce(&privkey.value(), &pubkey, &message, &aux_rand);
+        let k0 = compute_nonce(&d, &pubkey, &message, &aux_rand);
         // sign with k0
         let sig_with_k0 = schnorr.sign_with_k0(&message, &k0, &privkey).unwrap();
         // sign fhe with k0
@@ -491,6 +599,111 @@ mod tests {
         assert!(Schnorr::verify(&message, &pubkey.x.value().to_bytes_be(), &sig_with_k0.to_bytes()));
     }
 
+    #[test]
+    fn test_schnorr_fhe_with_key_table() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_keys) = generate_keys(config);
+        set_server_key(server_keys);
+
+        let seckey_bytes = hex::decode("0000000000000000000000000000000000000000000000000000000000000003").unwrap();
+        let message = hex::decode("0000000000000000000000000000000000000000000000000000000000000000").unwrap();
+        let aux_rand = hex::decode("0000000000000000000000000000000000000000000000000000000000000000").unwrap();
+
+        let privkey = Scalar::new(BigUint::from_bytes_be(&seckey_bytes));
+        let privkey_fhe = BigUintFHE::new(privkey.value().clone(), &client_key).unwrap();
+        let key_table = EncryptedKeyTable::new(&privkey_fhe, &get_curve_order(), 4).unwrap();
+        let (d, pubkey) = key_pair_with_even_y(&privkey);
+
+        let schnorr = Schnorr::new();
+        let k0 = compute_nonce(&d, &pubkey, &message, &aux_rand);
+        let sig_with_k0 = schnorr.sign_with_k0(&message, &k0, &privkey).unwrap();
+        let sig_table = schnorr.sign_fhe_with_k0_table(&message, &k0, &privkey, &key_table).decrypt(&client_key);
+
+        assert_eq!(sig_with_k0.to_bytes(), sig_table.to_bytes());
+    }
+
+    /// Signs every BIP-340 vector that has a secret key with the private key encrypted in `B`,
+    /// through both `sign_fhe_with_backend` and `sign_fhe_with_k0`
+    fn check_fhe_vectors<B: FheBackend>(client_key: &B::ClientKey) {
+        let csv_content = include_str!("../tests/test_vectors.csv");
+        let schnorr = Schnorr::new();
+
+        for line in csv_content.lines().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() < 7 || fields[1].is_empty() { continue; }
+
+            let seckey = Scalar::new(BigUint::from_bytes_be(&hex::decode(fields[1]).unwrap()));
+            let aux_rand = hex::decode(fields[3]).unwrap();
+            let message = hex::decode(fields[4]).unwrap();
+            let expected_sig = hex::decode(fields[5]).unwrap();
+
+            let sig = schnorr.sign_fhe_with_backend::<B>(&message, &aux_rand, &seckey, client_key).unwrap();
+            assert_eq!(sig.to_bytes(), expected_sig, "vector #{}", fields[0]);
+
+            let (d, pubkey) = key_pair_with_even_y(&seckey);
+            let k0 = compute_nonce(&d, &pubkey, &message, &aux_rand);
+            let privkey_fhe = B::encrypt(seckey.value(), client_key).unwrap();
+            let sig_with_k0 = schnorr.sign_fhe_with_k0(&message, &k0, &seckey, &privkey_fhe, client_key).unwrap();
+            assert_eq!(sig_with_k0.to_bytes(), expected_sig, "vector #{}", fields[0]);
+        }
+    }
+
+    #[test]
+    fn test_schnorr_fhe_vectors() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_keys) = generate_keys(config);
+        set_server_key(server_keys);
+
+        check_fhe_vectors::<BigUintFHE>(&client_key);
+    }
+
+    #[test]
+    fn test_schnorr_fhe_native_backend() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_keys) = generate_keys(config);
+        set_server_key(server_keys);
+
+        check_fhe_vectors::<FheUint512>(&client_key);
+    }
+
+    #[test]
+    fn test_schnorr_fhe_mock_backend() {
+        let csv_content = include_str!("../tests/test_vectors.csv");
+        let schnorr = Schnorr::new();
+        let key = MockClientKey;
+
+        for line in csv_content.lines().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() < 7 || fields[1].is_empty() { continue; }
+
+            let seckey = Scalar::new(BigUint::from_bytes_be(&hex::decode(fields[1]).unwrap()));
+            let aux_rand = hex::decode(fields[3]).unwrap();
+            let message = hex::decode(fields[4]).unwrap();
+            let expected_sig = hex::decode(fields[5]).unwrap();
+
+            // The whole FHE signing path runs on cleartext mock ciphertexts
+            MockUintFHE::reset_op_counts();
+            let sig = schnorr.sign_fhe_with_backend::<MockUintFHE>(&message, &aux_rand, &seckey, &key).unwrap();
+            assert_eq!(sig.to_bytes(), expected_sig, "vector #{}", fields[0]);
+            let counts = MockUintFHE::op_counts();
+            assert_eq!((counts.encrypt, counts.mul, counts.mul_clear, counts.reduce_mod), (1, 0, 1, 1));
+
+            let (d, pubkey) = key_pair_with_even_y(&seckey);
+            let k0 = compute_nonce(&d, &pubkey, &message, &aux_rand);
+            let privkey_fhe = MockUintFHE::encrypt(seckey.value(), &key).unwrap();
+            let sig_with_k0 = schnorr.sign_fhe_with_k0(&message, &k0, &seckey, &privkey_fhe, &key).unwrap();
+            assert_eq!(sig_with_k0.to_bytes(), expected_sig, "vector #{}", fields[0]);
+
+            // Signing from a key table needs no multiplication at all
+            let key_table = EncryptedKeyTable::new(&privkey_fhe, &get_curve_order(), 4).unwrap();
+            MockUintFHE::reset_op_counts();
+            let sig_table = schnorr.sign_fhe_with_k0_table(&message, &k0, &seckey, &key_table).decrypt(&key);
+            assert_eq!(sig_table.to_bytes(), expected_sig, "vector #{}", fields[0]);
+            let counts = MockUintFHE::op_counts();
+            assert_eq!((counts.mul, counts.mul_clear), (0, 0));
+        }
+    }
+
     #[test]
     fn test_schnorr_bip340() {
         // Test vector from BIP-340
@@ -504,7 +717,7 @@ mod tests {
         let sig = schnorr.sign(&message, &aux_rand, &seckey);
         assert!(sig.is_ok());
         let sig = sig.unwrap();
-        let pubkey = get_public_key_with_even_y(&seckey);
+        let (_, pubkey) = key_pair_with_even_y(&seckey);
 
         assert_eq!(sig.to_bytes(), expected_sig);
         assert!(Schnorr::verify(&message, &pubkey.x.value().to_bytes_be(), &expected_sig));
@@ -517,8 +730,8 @@ mod tests {
         let aux_rand = hex::decode("0000000000000000000000000000000000000000000000000000000000000000").unwrap();
         let privkey = Scalar::new(BigUint::from_bytes_be(&seckey_bytes));
         let schnorr = Schnorr::new();
-        let pubkey = get_public_key_with_even_y(&privkey);
-        let k0 = compute_nonce(&privkey.value(), &pubkey, &message, &aux_rand);
+        let (d, pubkey) = key_pair_with_even_y(&privkey);
+        let k0 = compute_nonce(&d, &pubkey, &message, &aux_rand);
         let sig_with_k0 = schnorr.sign_with_k0(&message, &k0, &privkey).unwrap();
 
         let sig = schnorr.sign(&message, &aux_rand, &privkey).unwrap();
@@ -532,7 +745,7 @@ mod tests {
         let csv_content = include_str!("../tests/test_vectors.csv");
         let mut all_passed = true;
 
-        for (i, line) in csv_content.lines().skip(1).enumerate() {
+        for line in csv_content.lines().skip(1) {
             let fields: Vec<&str> = line.split(',').collect();
             if fields.len() < 7 { continue; }
 
@@ -3,8 +3,8 @@ use crate::scalar::{Scalar, new_base_field, get_field_size};
 use std::{clone::Clone, fmt::{Debug, Display}, ops::{Sub, Neg}};
 use num_bigint::BigUint;
 
-/// Implementation of the secp256k1 elliptic curve: y^2 = x^3 + 7 (mod p)
-/// The curve is defined over the prime field GF(p) where p is the field size.
+// Implementation of the secp256k1 elliptic curve: y^2 = x^3 + 7 (mod p)
+// The curve is defined over the prime field GF(p) where p is the field size.
 
 // Curve parameters
 const A: u32 = 0; // Coefficient of x term
@@ -76,7 +76,7 @@ impl Point {
 
                 return Self::new(x3, y3, false);
             }
-            if &self.y == &(-other.y.clone()) {
+            if self.y == (-other.y.clone()) {
                 return Self::infinity();
             }
         }
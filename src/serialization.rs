@@ -0,0 +1,273 @@
+//! Versioned binary format for `BigUintFHE` values, e.g. encrypted private keys kept at rest.
+//!
+//! Layout (integers are little-endian):
+//!
+//! | field       | size     | description                                                  |
+//! |-------------|----------|--------------------------------------------------------------|
+//! | magic       | 4 bytes  | `b"BUFH"`                                                    |
+//! | version     | 1 byte   | `FORMAT_VERSION`, currently 1                                |
+//! | kind        | 1 byte   | 0 = `FheUint32` digits, 1 = `CompressedFheUint32` digits     |
+//! | limb_bits   | 1 byte   | bit width of each digit, currently 32                        |
+//! | limb_count  | 4 bytes  | number of digits that follow                                 |
+//! | bound_len   | 4 bytes  | length of the public bound                                   |
+//! | fingerprint | 32 bytes | SHA-256 of the bincode-encoded tfhe `Config`                 |
+//! | bound       | variable | public upper bound of the value, little-endian               |
+//! | digits      | variable | per digit: 8-byte length, then the tfhe `safe_serialize` output |
+//!
+//! Digits are stored least significant first. Deserialization enforces a caller-provided size
+//! limit and rejects unknown versions, unexpected kinds, bounds that do not match the digit count
+//! and data produced under other parameters.
+
+use serde::Serialize;
+use sha2::{Sha256, Digest};
+use tfhe::named::Named;
+use tfhe::safe_serialization::{safe_deserialize, safe_serialize};
+use tfhe::prelude::*;
+use tfhe::{ClientKey, CompressedFheUint32, Config, FheUint32, Versionize};
+use num_bigint::BigUint;
+use crate::biguint::BigUintFHE;
+
+/// Magic bytes at the start of every serialized value
+const MAGIC: &[u8; 4] = b"BUFH";
+
+/// Current version of the binary format
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Bit width of the serialized digits
+const LIMB_BITS: u8 = 32;
+
+/// Size of the fixed header: magic, version, kind, limb_bits, limb_count, bound_len and fingerprint
+const HEADER_SIZE: usize = 4 + 1 + 1 + 1 + 4 + 4 + 32;
+
+/// Kind byte for regular `FheUint32` digits
+const KIND_DIGITS: u8 = 0;
+
+/// Kind byte for `CompressedFheUint32` digits
+const KIND_COMPRESSED_DIGITS: u8 = 1;
+
+/// Serializes each digit with tfhe's versioned safe serialization
+fn serialize_digits<T: Serialize + Named + Versionize>(digits: &[T]) -> Result<Vec<Vec<u8>>, tfhe::Error> {
+    digits.iter()
+        .map(|digit| {
+            let mut buffer = Vec::new();
+            safe_serialize(digit, &mut buffer, u64::MAX)
+                .map_err(|e| tfhe::Error::from(format!("Failed to serialize digit: {}", e)))?;
+            Ok(buffer)
+        })
+        .collect()
+}
+
+/// Returns a fingerprint identifying the tfhe parameters of a `Config`
+pub fn parameter_fingerprint(config: &Config) -> Result<[u8; 32], tfhe::Error> {
+    let encoded = bincode::serialize(config)
+        .map_err(|e| tfhe::Error::from(format!("Failed to encode config: {}", e)))?;
+    Ok(Sha256::digest(encoded).into())
+}
+
+/// A `BigUintFHE` whose digits are compressed, roughly halving its size for storage.
+///
+/// tfhe compresses a ciphertext when it is encrypted, so only the client key owner can produce
+/// this form. Recompressing the result of a computation would need a compression key in the
+/// `Config`, which this crate does not set up; store such values with `BigUintFHE::to_bytes`.
+/// Compressed values cannot be used in arithmetic, call `decompress` first.
+#[derive(Clone)]
+pub struct CompressedBigUintFHE {
+    digits: Vec<CompressedFheUint32>,
+    bound: BigUint,
+}
+
+impl CompressedBigUintFHE {
+    /// Encrypts a BigUint value directly into compressed form with the client key
+    pub fn new(value: BigUint, client_key: &ClientKey) -> Result<Self, tfhe::Error> {
+        let digits = value.to_u32_digits().into_iter()
+            .map(|d| CompressedFheUint32::try_encrypt(d, client_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        // The value is secret, so the bound only reflects its digit count
+        let bound = BigUintFHE::max_value(digits.len());
+        Ok(Self { digits, bound })
+    }
+
+    /// Decompresses into a BigUintFHE that can take part in arithmetic
+    pub fn decompress(&self) -> BigUintFHE {
+        let digits = self.digits.iter().map(|d| d.decompress()).collect();
+        BigUintFHE::from_encrypted_digits(digits).with_bound(&self.bound)
+    }
+
+    /// Serializes the value using the format described in the module documentation
+    pub fn to_bytes(&self, config: &Config) -> Result<Vec<u8>, tfhe::Error> {
+        let digits = serialize_digits(&self.digits)?;
+        write_value(KIND_COMPRESSED_DIGITS, &self.bound, &digits, config)
+    }
+
+    /// Deserializes a value written by `to_bytes`, rejecting inputs larger than `size_limit` bytes
+    pub fn from_bytes(bytes: &[u8], config: &Config, size_limit: u64) -> Result<Self, tfhe::Error> {
+        let (bound, digits) = read_value(bytes, KIND_COMPRESSED_DIGITS, config, size_limit)?;
+        let digits = digits.into_iter()
+            .map(|d| safe_deserialize::<CompressedFheUint32>(d, d.len() as u64).map_err(tfhe::Error::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { digits, bound })
+    }
+}
+
+impl BigUintFHE {
+    /// Serializes the value using the format described in the module documentation
+    pub fn to_bytes(&self, config: &Config) -> Result<Vec<u8>, tfhe::Error> {
+        let digits = serialize_digits(self.digits())?;
+        write_value(KIND_DIGITS, self.bound(), &digits, config)
+    }
+
+    /// Deserializes a value written by `to_bytes`, rejecting inputs larger than `size_limit` bytes
+    pub fn from_bytes(bytes: &[u8], config: &Config, size_limit: u64) -> Result<Self, tfhe::Error> {
+        let (bound, digits) = read_value(bytes, KIND_DIGITS, config, size_limit)?;
+        let digits = digits.into_iter()
+            .map(|d| safe_deserialize::<FheUint32>(d, d.len() as u64).map_err(tfhe::Error::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(BigUintFHE::from_encrypted_digits(digits).with_bound(&bound))
+    }
+}
+
+/// Writes the header and the bound, followed by the length-prefixed digits
+fn write_value(kind: u8, bound: &BigUint, digits: &[Vec<u8>], config: &Config) -> Result<Vec<u8>, tfhe::Error> {
+    let bound_bytes = bound.to_bytes_le();
+    let payload_size: usize = digits.iter().map(|d| 8 + d.len()).sum();
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + bound_bytes.len() + payload_size);
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(FORMAT_VERSION);
+    bytes.push(kind);
+    bytes.push(LIMB_BITS);
+    bytes.extend_from_slice(&(digits.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(bound_bytes.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&parameter_fingerprint(config)?);
+    bytes.extend_from_slice(&bound_bytes);
+
+    for digit in digits {
+        bytes.extend_from_slice(&(digit.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(digit);
+    }
+
+    Ok(bytes)
+}
+
+/// Validates the header and splits the payload into the bound and the serialized digits
+fn read_value<'a>(bytes: &'a [u8], kind: u8, config: &Config, size_limit: u64) -> Result<(BigUint, Vec<&'a [u8]>), tfhe::Error> {
+    if bytes.len() as u64 > size_limit {
+        return Err(format!("Serialized value is {} bytes, over the {} byte limit", bytes.len(), size_limit).into());
+    }
+    if bytes.len() < HEADER_SIZE {
+        return Err("Serialized value is shorter than its header".into());
+    }
+    if &bytes[0..4] != MAGIC {
+        return Err("Serialized value does not start with the BigUintFHE magic bytes".into());
+    }
+    if bytes[4] != FORMAT_VERSION {
+        return Err(format!("Unsupported BigUintFHE format version {}", bytes[4]).into());
+    }
+    if bytes[5] != kind {
+        return Err(format!("Unexpected BigUintFHE kind {}, expected {}", bytes[5], kind).into());
+    }
+    if bytes[6] != LIMB_BITS {
+        return Err(format!("Unsupported limb width of {} bits", bytes[6]).into());
+    }
+    let limb_count = u32::from_le_bytes(bytes[7..11].try_into().unwrap()) as usize;
+    let bound_len = u32::from_le_bytes(bytes[11..15].try_into().unwrap()) as usize;
+    if bytes[15..HEADER_SIZE] != parameter_fingerprint(config)? {
+        return Err("Serialized value was produced under different tfhe parameters".into());
+    }
+
+    let mut rest = &bytes[HEADER_SIZE..];
+    if bound_len > rest.len() {
+        return Err("Serialized value is truncated".into());
+    }
+    let bound = BigUint::from_bytes_le(&rest[..bound_len]);
+    rest = &rest[bound_len..];
+    if BigUintFHE::digit_count(&bound) != limb_count {
+        return Err(format!("Bound of {} bits does not match the limb count {}", bound.bits(), limb_count).into());
+    }
+
+    // Every digit needs at least its length prefix, so this bounds the allocation below
+    if limb_count > rest.len() / 8 {
+        return Err(format!("Limb count {} does not fit in the serialized value", limb_count).into());
+    }
+
+    let mut digits = Vec::with_capacity(limb_count);
+    for _ in 0..limb_count {
+        if rest.len() < 8 {
+            return Err("Serialized value is truncated".into());
+        }
+        let len = u64::from_le_bytes(rest[0..8].try_into().unwrap());
+        rest = &rest[8..];
+        if len > rest.len() as u64 {
+            return Err("Serialized value is truncated".into());
+        }
+        let (digit, tail) = rest.split_at(len as usize);
+        digits.push(digit);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return Err("Serialized value has trailing bytes".into());
+    }
+
+    Ok((bound, digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tfhe::ConfigBuilder;
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let value = BigUint::parse_bytes(b"123456789123456789", 10).unwrap();
+        let encrypted = BigUintFHE::new(value.clone(), &client_key).unwrap();
+
+        let bytes = encrypted.to_bytes(&config).unwrap();
+        assert_eq!(&bytes[0..4], MAGIC);
+        assert_eq!(bytes[4], 1);
+        let restored = BigUintFHE::from_bytes(&bytes, &config, bytes.len() as u64).unwrap();
+        assert_eq!(restored.to_biguint(&client_key), value);
+
+        // A bound declared before serialization survives the roundtrip
+        let bounded = encrypted.clone().with_bound(&value);
+        let bytes_bounded = bounded.to_bytes(&config).unwrap();
+        let restored = BigUintFHE::from_bytes(&bytes_bounded, &config, bytes_bounded.len() as u64).unwrap();
+        assert_eq!(restored.bound(), &value);
+        assert_eq!(restored.to_biguint(&client_key), value);
+
+        // Size limit, truncation and kind mismatches are rejected
+        assert!(BigUintFHE::from_bytes(&bytes, &config, bytes.len() as u64 - 1).is_err());
+        assert!(BigUintFHE::from_bytes(&bytes[..bytes.len() - 1], &config, u64::MAX).is_err());
+        assert!(CompressedBigUintFHE::from_bytes(&bytes, &config, u64::MAX).is_err());
+
+        // Other format versions are rejected
+        let mut bytes_other_version = bytes.clone();
+        bytes_other_version[4] = FORMAT_VERSION + 1;
+        assert!(BigUintFHE::from_bytes(&bytes_other_version, &config, u64::MAX).is_err());
+
+        // Data produced under other parameters is rejected
+        let other_config = ConfigBuilder::default_with_small_encryption().build();
+        assert!(BigUintFHE::from_bytes(&bytes, &other_config, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_compressed_serialization_roundtrip() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = tfhe::generate_keys(config);
+        tfhe::set_server_key(server_key);
+
+        let value = BigUint::parse_bytes(b"123456789123456789", 10).unwrap();
+        let encrypted = BigUintFHE::new(value.clone(), &client_key).unwrap();
+        let uncompressed_size = encrypted.to_bytes(&config).unwrap().len();
+
+        // Encrypted straight into compressed form by the key owner
+        let compressed = CompressedBigUintFHE::new(value.clone(), &client_key).unwrap();
+        let bytes = compressed.to_bytes(&config).unwrap();
+        assert!(bytes.len() < uncompressed_size);
+        let restored = CompressedBigUintFHE::from_bytes(&bytes, &config, bytes.len() as u64).unwrap();
+        assert_eq!(restored.decompress().to_biguint(&client_key), value);
+    }
+}